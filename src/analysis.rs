@@ -1,7 +1,58 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
 use serde::{Deserialize, Serialize};
+use crate::error::{Result, SurveyError};
+use crate::stats;
 use crate::survey::QuestionType;
 
+/// Method used to compute a confidence interval for a proportion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntervalMethod {
+    Wilson,
+    ClopperPearson,
+}
+
+impl IntervalMethod {
+    /// Compute the interval for `successes` out of `trials` as fractions in [0, 1]
+    pub fn interval(&self, successes: usize, trials: usize, level: f64) -> (f64, f64) {
+        match self {
+            IntervalMethod::Wilson => stats::wilson_interval(successes, trials, level),
+            IntervalMethod::ClopperPearson => stats::clopper_pearson_interval(successes, trials, level),
+        }
+    }
+}
+
+impl FromStr for IntervalMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "wilson" => Ok(IntervalMethod::Wilson),
+            "clopper-pearson" | "clopper_pearson" | "exact" => Ok(IntervalMethod::ClopperPearson),
+            _ => Err(format!("Unknown interval method: {} (expected wilson or clopper-pearson)", s)),
+        }
+    }
+}
+
+impl fmt::Display for IntervalMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalMethod::Wilson => write!(f, "Wilson"),
+            IntervalMethod::ClopperPearson => write!(f, "Clopper-Pearson"),
+        }
+    }
+}
+
+/// Confidence interval of an option's share, in percent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub level: f64,
+    pub method: IntervalMethod,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnswerDistribution {
     pub question_id: usize,
@@ -9,6 +60,8 @@ pub struct AnswerDistribution {
     pub question_type: QuestionType,
     pub distribution: HashMap<String, (usize, f64)>, // (count, percentage)
     pub total_responses: usize,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub intervals: HashMap<String, ConfidenceInterval>, // empty unless requested
 }
 
 impl AnswerDistribution {
@@ -22,15 +75,44 @@ impl AnswerDistribution {
 
         // Sort by count (descending)
         let mut items: Vec<_> = self.distribution.iter().collect();
+        #[allow(clippy::unnecessary_sort_by)]
         items.sort_by(|a, b| b.1.0.cmp(&a.1.0));
 
         for (option, (count, percentage)) in items {
-            output.push_str(&format!("  {}: {} ({:.1}%)\n", option, count, percentage));
+            match self.intervals.get(option) {
+                Some(ci) => output.push_str(&format!(
+                    "  {}: {} ({:.1}%, {:.0}% CI {:.1}-{:.1}%)\n",
+                    option, count, percentage, ci.level * 100.0, ci.lower, ci.upper
+                )),
+                None => output.push_str(&format!("  {}: {} ({:.1}%)\n", option, count, percentage)),
+            }
         }
 
         output
     }
 
+    /// Attach a confidence interval to every option's share
+    pub fn with_confidence_intervals(mut self, level: f64, method: IntervalMethod) -> Result<Self> {
+        if !(level > 0.0 && level < 1.0) {
+            return Err(SurveyError::InvalidParameter(format!(
+                "Confidence level must be between 0 and 1, got {}",
+                level
+            )));
+        }
+
+        self.intervals = self
+            .distribution
+            .iter()
+            .map(|(option, (count, _))| {
+                let (lower, upper) = method.interval(*count, self.total_responses, level);
+                let interval = ConfidenceInterval { lower: lower * 100.0, upper: upper * 100.0, level, method };
+                (option.clone(), interval)
+            })
+            .collect();
+
+        Ok(self)
+    }
+
     /// Get the most popular answer
     pub fn most_popular(&self) -> Option<(String, usize, f64)> {
         self.distribution
@@ -50,8 +132,264 @@ impl AnswerDistribution {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Subset {
+pub struct WeightedDistribution {
     pub question_id: usize,
+    pub question_text: String,
+    pub question_type: QuestionType,
+    pub distribution: HashMap<String, (f64, f64)>, // (weighted count, percentage)
+    pub total_weight: f64,
+    pub total_responses: usize, // unweighted
+    pub effective_sample_size: f64,
+}
+
+impl WeightedDistribution {
+    /// Display the weighted distribution in a formatted way
+    pub fn display(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Question {}: {}\n", self.question_id, self.question_text));
+        output.push_str(&format!("Type: {:?}\n", self.question_type));
+        output.push_str(&format!(
+            "Total Responses: {} (weighted {:.1}, effective sample size {:.1})\n",
+            self.total_responses, self.total_weight, self.effective_sample_size
+        ));
+        output.push_str("Weighted Distribution:\n");
+
+        let mut items: Vec<_> = self.distribution.iter().collect();
+        items.sort_by(|a, b| b.1.0.total_cmp(&a.1.0));
+
+        for (option, (weight, percentage)) in items {
+            output.push_str(&format!("  {}: {:.1} ({:.1}%)\n", option, weight, percentage));
+        }
+
+        output
+    }
+
+    /// Get the most popular answer by weighted count
+    pub fn most_popular(&self) -> Option<(String, f64, f64)> {
+        self.distribution
+            .iter()
+            .max_by(|a, b| a.1.0.total_cmp(&b.1.0))
+            .map(|(option, (weight, percentage))| (option.clone(), *weight, *percentage))
+    }
+}
+
+/// Contingency table of two choice questions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossTab {
+    pub row_question_id: usize,
+    pub row_question_text: String,
+    pub column_question_id: usize,
+    pub column_question_text: String,
+    pub row_options: Vec<String>,
+    pub column_options: Vec<String>,
+    pub counts: Vec<Vec<f64>>, // [row][column], weighted when `weighted` is set
+    pub total_responses: usize, // unweighted respondents who answered both questions
+    pub effective_sample_size: f64,
+    pub weighted: bool,
+}
+
+impl CrossTab {
+    /// Sum of each row
+    pub fn row_totals(&self) -> Vec<f64> {
+        self.counts.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    /// Sum of each column
+    pub fn column_totals(&self) -> Vec<f64> {
+        (0..self.column_options.len())
+            .map(|col| self.counts.iter().map(|row| row[col]).sum())
+            .collect()
+    }
+
+    /// Cell counts as percentages of their row total
+    pub fn row_percentages(&self) -> Vec<Vec<f64>> {
+        self.counts
+            .iter()
+            .map(|row| {
+                let total: f64 = row.iter().sum();
+                row.iter()
+                    .map(|count| if total > 0.0 { count / total * 100.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Display the cross-tabulation with row percentages
+    pub fn display(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!(
+            "Question {} ({}) by Question {} ({})\n",
+            self.row_question_id, self.row_question_text, self.column_question_id, self.column_question_text
+        ));
+        output.push_str(&format!("Total Responses: {}", self.total_responses));
+        if self.weighted {
+            output.push_str(&format!(" (effective sample size {:.1})", self.effective_sample_size));
+        }
+        output.push('\n');
+
+        let percentages = self.row_percentages();
+        for ((option, row), row_percentages) in self.row_options.iter().zip(&self.counts).zip(&percentages) {
+            output.push_str(&format!("  {}:\n", option));
+            for ((column, count), percentage) in self.column_options.iter().zip(row).zip(row_percentages) {
+                if self.weighted {
+                    output.push_str(&format!("    {}: {:.1} ({:.1}%)\n", column, count, percentage));
+                } else {
+                    output.push_str(&format!("    {}: {} ({:.1}%)\n", column, count, percentage));
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Summary statistics of a numeric question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumericSummary {
+    pub question_id: usize,
+    pub question_text: String,
+    pub count: usize, // unweighted number of numeric answers
+    pub total_weight: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    pub effective_sample_size: f64,
+    pub weighted: bool,
+}
+
+impl NumericSummary {
+    /// Display the summary in a formatted way
+    pub fn display(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Question {}: {}\n", self.question_id, self.question_text));
+        output.push_str(&format!("Count: {}", self.count));
+        if self.weighted {
+            output.push_str(&format!(
+                " (weighted {:.1}, effective sample size {:.1})",
+                self.total_weight, self.effective_sample_size
+            ));
+        }
+        output.push('\n');
+        output.push_str(&format!("Mean: {:.2}\n", self.mean));
+        output.push_str(&format!("Std Dev: {:.2}\n", self.std_dev));
+        output.push_str(&format!("Min: {:.2}\n", self.min));
+        output.push_str(&format!("Median: {:.2}\n", self.median));
+        output.push_str(&format!("Max: {:.2}\n", self.max));
+        output
+    }
+}
+
+/// Multiple-comparison correction applied to per-option p-values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Correction {
+    None,
+    Holm,
+    BenjaminiHochberg,
+}
+
+impl Correction {
+    /// Adjust p-values, keeping their order
+    pub fn adjust(&self, p_values: &[f64]) -> Vec<f64> {
+        match self {
+            Correction::None => p_values.to_vec(),
+            Correction::Holm => stats::holm_adjust(p_values),
+            Correction::BenjaminiHochberg => stats::benjamini_hochberg_adjust(p_values),
+        }
+    }
+}
+
+impl FromStr for Correction {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Correction::None),
+            "holm" => Ok(Correction::Holm),
+            "bh" | "benjamini-hochberg" | "fdr" => Ok(Correction::BenjaminiHochberg),
+            _ => Err(format!("Unknown correction: {} (expected none, holm or bh)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Correction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Correction::None => write!(f, "none"),
+            Correction::Holm => write!(f, "Holm"),
+            Correction::BenjaminiHochberg => write!(f, "Benjamini-Hochberg"),
+        }
+    }
+}
+
+/// Side-by-side shares of one option in two subsets with a two-proportion z-test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionComparison {
+    pub option: String,
+    pub left_count: usize,
+    pub left_percentage: f64,
+    pub right_count: usize,
+    pub right_percentage: f64,
+    pub difference: f64, // percentage points, left minus right
+    pub z_score: f64,
+    pub p_value: f64,
+    pub adjusted_p_value: f64,
+    pub significant: bool,
+}
+
+/// Comparison of two subsets' answers to the same question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsetComparison {
+    pub question_id: usize,
+    pub question_text: String,
+    pub left_label: String,
+    pub right_label: String,
+    pub left_total: usize,
+    pub right_total: usize,
+    pub correction: Correction,
+    pub alpha: f64,
+    pub options: Vec<OptionComparison>, // sorted by absolute difference (descending)
+}
+
+impl SubsetComparison {
+    /// Display the comparison as a table
+    pub fn display(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Question {}: {}\n", self.question_id, self.question_text));
+        output.push_str(&format!(
+            "Comparing '{}' ({} responses) with '{}' ({} responses)\n",
+            self.left_label, self.left_total, self.right_label, self.right_total
+        ));
+        output.push_str(&format!("Correction: {}, alpha = {}\n", self.correction, self.alpha));
+
+        for option in &self.options {
+            output.push_str(&format!(
+                "  {}: {:.1}% vs {:.1}% ({:+.1} pp, z = {:.2}, adjusted p = {:.4}){}\n",
+                option.option,
+                option.left_percentage,
+                option.right_percentage,
+                option.difference,
+                option.z_score,
+                option.adjusted_p_value,
+                if option.significant { " *" } else { "" }
+            ));
+        }
+
+        output
+    }
+
+    /// Get the options whose difference is significant after correction
+    pub fn significant(&self) -> Vec<&OptionComparison> {
+        self.options.iter().filter(|o| o.significant).collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subset {
+    pub question_id: usize, // for a group subset, the group's first question
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>, // question group the option was matched across, if any
     pub option: String,
     pub respondent_ids: Vec<usize>,
     pub total_respondents: usize,
@@ -74,11 +412,15 @@ impl Subset {
 
     /// Display subset information
     pub fn display(&self) -> String {
+        let source = match &self.group {
+            Some(group) => format!("Group '{}'", group),
+            None => format!("Question {}", self.question_id),
+        };
         format!(
-            "Subset for Question {} - Option '{}'\n\
+            "Subset for {} - Option '{}'\n\
             Size: {} respondents ({:.1}% of total)\n\
             Respondent IDs: {:?}",
-            self.question_id,
+            source,
             self.option,
             self.size(),
             self.percentage(),
@@ -99,12 +441,142 @@ impl Subset {
             .cloned()
             .collect()
     }
+
+    /// Respondents in this subset or the other one
+    pub fn union(&self, other: &Subset) -> Subset {
+        self.combine(other, "|", |left, right| left || right)
+    }
+
+    /// Respondents in both this subset and the other one
+    pub fn intersection(&self, other: &Subset) -> Subset {
+        self.combine(other, "&", |left, right| left && right)
+    }
+
+    /// Respondents in this subset but not the other one
+    pub fn difference(&self, other: &Subset) -> Subset {
+        self.combine(other, "-", |left, right| left && !right)
+    }
+
+    /// Set operation on respondents; the result keeps this subset's question and labels the option with the operation
+    fn combine(&self, other: &Subset, operator: &str, keep: impl Fn(bool, bool) -> bool) -> Subset {
+        let left: BTreeSet<usize> = self.respondent_ids.iter().copied().collect();
+        let right: BTreeSet<usize> = other.respondent_ids.iter().copied().collect();
+        Subset {
+            question_id: self.question_id,
+            group: self.group.clone(),
+            option: format!("{} {} {}", self.option, operator, other.option),
+            respondent_ids: left
+                .union(&right)
+                .copied()
+                .filter(|id| keep(left.contains(id), right.contains(id)))
+                .collect(),
+            total_respondents: self.total_respondents,
+        }
+    }
+}
+
+/// Admired/desired statistics for a single option of a question family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmiredDesiredOption {
+    pub option: String,
+    pub worked_with: usize,
+    pub want_to_work_with: usize,
+    pub continuing: usize, // worked with it and want to keep working with it
+    pub admired: f64,      // percentage of `worked_with` who want to continue
+    pub desired: f64,      // percentage of all respondents who want to work with it
+}
+
+/// Respondents who have worked with one option and want to work with another
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migration {
+    pub from: String,
+    pub to: String,
+    pub count: usize,
+}
+
+/// Admired/desired analysis of a `*HaveWorkedWith` / `*WantToWorkWith` question pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdmiredDesired {
+    pub family: String,
+    pub have_question_id: usize,
+    pub want_question_id: usize,
+    pub options: Vec<AdmiredDesiredOption>, // sorted by admired (descending)
+    pub migrations: Vec<Migration>,         // sorted by count (descending)
+    pub total_respondents: usize,
+}
+
+impl AdmiredDesired {
+    /// Display the admired/desired table and the top migration flows
+    pub fn display(&self, top_migrations: usize) -> String {
+        let mut output = String::new();
+        output.push_str(&format!(
+            "{}: admired/desired (Questions {} and {})\n",
+            self.family, self.have_question_id, self.want_question_id
+        ));
+        output.push_str(&format!("Total Respondents: {}\n", self.total_respondents));
+        output.push_str("Options:\n");
+
+        for option in &self.options {
+            output.push_str(&format!(
+                "  {}: admired {:.1}% ({} of {}), desired {:.1}% ({})\n",
+                option.option,
+                option.admired,
+                option.continuing,
+                option.worked_with,
+                option.desired,
+                option.want_to_work_with
+            ));
+        }
+
+        if top_migrations > 0 && !self.migrations.is_empty() {
+            output.push_str("Top migrations:\n");
+            for migration in self.top_migrations(top_migrations) {
+                output.push_str(&format!(
+                    "  {} -> {}: {}\n",
+                    migration.from, migration.to, migration.count
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Get statistics for a single option
+    pub fn option(&self, option: &str) -> Option<&AdmiredDesiredOption> {
+        self.options.iter().find(|o| o.option == option)
+    }
+
+    /// Get the N largest migration flows
+    pub fn top_migrations(&self, n: usize) -> &[Migration] {
+        &self.migrations[..self.migrations.len().min(n)]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_subset_set_operations() {
+        let subset = |option: &str, respondent_ids: Vec<usize>| Subset {
+            question_id: 1,
+            group: None,
+            option: option.to_string(),
+            respondent_ids,
+            total_respondents: 10,
+        };
+        let rust = subset("Rust", vec![1, 2, 3, 5]);
+        let remote = subset("Remote", vec![5, 3, 8]);
+
+        let both = rust.intersection(&remote);
+        assert_eq!(both.respondent_ids, vec![3, 5]);
+        assert_eq!(both.option, "Rust & Remote");
+        assert_eq!(rust.union(&remote).respondent_ids, vec![1, 2, 3, 5, 8]);
+        assert_eq!(rust.difference(&remote).respondent_ids, vec![1, 2]);
+        assert_eq!(remote.difference(&rust).option, "Remote - Rust");
+        assert_eq!(rust.union(&remote).percentage(), 50.0);
+    }
+
     #[test]
     fn test_answer_distribution() {
         let mut distribution = HashMap::new();
@@ -118,6 +590,7 @@ mod tests {
             question_type: QuestionType::SingleChoice,
             distribution,
             total_responses: 500,
+            intervals: HashMap::new(),
         };
 
         let most_popular = answer_dist.most_popular().unwrap();
@@ -132,6 +605,7 @@ mod tests {
     fn test_subset() {
         let subset = Subset {
             question_id: 1,
+            group: None,
             option: "Rust".to_string(),
             respondent_ids: vec![1, 2, 3, 4, 5],
             total_respondents: 100,
@@ -147,6 +621,7 @@ mod tests {
     fn test_subset_intersection() {
         let subset1 = Subset {
             question_id: 1,
+            group: None,
             option: "Rust".to_string(),
             respondent_ids: vec![1, 2, 3, 4, 5],
             total_respondents: 100,
@@ -154,6 +629,7 @@ mod tests {
 
         let subset2 = Subset {
             question_id: 2,
+            group: None,
             option: "Senior".to_string(),
             respondent_ids: vec![3, 4, 5, 6, 7],
             total_respondents: 100,
@@ -162,4 +638,31 @@ mod tests {
         let intersection = subset1.intersect(&subset2);
         assert_eq!(intersection, vec![3, 4, 5]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_confidence_intervals() {
+        let mut distribution = HashMap::new();
+        distribution.insert("Yes".to_string(), (5, 50.0));
+        distribution.insert("No".to_string(), (5, 50.0));
+
+        let answer_dist = AnswerDistribution {
+            question_id: 1,
+            question_text: "Do you use Rust?".to_string(),
+            question_type: QuestionType::SingleChoice,
+            distribution,
+            total_responses: 10,
+            intervals: HashMap::new(),
+        };
+        assert!(!answer_dist.display().contains("CI"));
+        assert!(answer_dist.clone().with_confidence_intervals(95.0, IntervalMethod::Wilson).is_err());
+
+        let with_ci = answer_dist.with_confidence_intervals(0.95, IntervalMethod::Wilson).unwrap();
+        let yes = &with_ci.intervals["Yes"];
+        assert!((yes.lower - 23.66).abs() < 0.01);
+        assert!((yes.upper - 76.34).abs() < 0.01);
+        assert!(with_ci.display().contains("95% CI 23.7-76.3%"));
+
+        let json = serde_json::to_string(&with_ci).unwrap();
+        assert!(json.contains("\"method\":\"Wilson\""));
+    }
+}
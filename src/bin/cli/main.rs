@@ -0,0 +1,853 @@
+use clap::{Parser, Subcommand};
+use so_survey_analyzer::{
+    BarChart, Bootstrap, BootstrapMethod, Correction, IntervalMethod, NumericStatistic, Question, QuestionRef, RakeTarget,
+    Report, ReportFormat, SearchMatch, SearchMode, SearchTarget, StackedBarChart, SurveyAnalyzer, SurveyError, ToVegaLite,
+    Weights, Workspace,
+};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+mod output;
+mod repl;
+mod serve;
+mod session;
+
+use output::{Family, OutputFormat};
+
+/// Maximum number of raking passes before giving up
+const RAKING_ITERATIONS: usize = 100;
+
+/// Line width used for bar charts when `COLUMNS` is not set
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+#[derive(Parser)]
+#[command(name = "so-survey-cli")]
+#[command(about = "Stack Overflow Survey Data Analyzer")]
+#[command(version = "0.1.0")]
+struct Cli {
+    /// Path to the survey data file: .xlsx, .csv, .csv.gz or the .zip download
+    #[arg(short, long, default_value = "../so_2024_raw.xlsx")]
+    file: PathBuf,
+
+    /// Output format for results: table, json or csv (status messages go to stderr)
+    #[arg(long, global = true, default_value = "table")]
+    format: OutputFormat,
+
+    /// Weight respondents by the numeric values of this question (ID or column name)
+    #[arg(long, global = true, conflicts_with = "rake")]
+    weight_column: Option<QuestionRef>,
+
+    /// Weight respondents by raking to target marginals from a JSON file
+    /// (`[{"question": "Country", "marginals": {"Germany": 0.2, ...}}, ...]`)
+    #[arg(long, global = true)]
+    rake: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Display the survey structure (list of questions)
+    Structure {
+        /// Show only first N questions
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Show questions containing this term
+        #[arg(short, long)]
+        filter: Option<String>,
+        /// Group related columns into question families
+        #[arg(short, long)]
+        grouped: bool,
+    },
+    /// Search for questions or options
+    Search {
+        /// Search term
+        term: String,
+        /// Search in questions (default) or options
+        #[arg(short, long)]
+        options: bool,
+        /// Treat the term as a (case-insensitive) regular expression
+        #[arg(long, conflicts_with_all = ["fuzzy", "ranked"])]
+        regex: bool,
+        /// Fuzzy subsequence matching (e.g. "lhww" finds LanguageHaveWorkedWith)
+        #[arg(long, conflicts_with = "ranked")]
+        fuzzy: bool,
+        /// Ranked, typo-tolerant search across questions, options and text answers
+        #[arg(short, long, conflicts_with = "options")]
+        ranked: bool,
+        /// Maximum number of results
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    /// Create a subset of respondents
+    Subset {
+        /// Question ID or column name (e.g. LanguageHaveWorkedWith)
+        question: QuestionRef,
+        /// Answer option to filter by
+        option: String,
+    },
+    /// Display answer distribution for a question
+    Distribution {
+        /// Question ID or column name (e.g. LanguageHaveWorkedWith)
+        question: QuestionRef,
+        /// Minimum percentage threshold to display
+        #[arg(short, long, default_value = "0.0")]
+        threshold: f64,
+        /// Show confidence intervals at this level (e.g. 0.95)
+        #[arg(long)]
+        ci: Option<f64>,
+        /// Confidence interval method: wilson or clopper-pearson
+        #[arg(long, default_value = "wilson")]
+        ci_method: IntervalMethod,
+        /// Draw the distribution as Unicode bars scaled to the terminal width
+        #[arg(long)]
+        bars: bool,
+        /// Write a bar chart of the distribution to this SVG file
+        #[arg(long)]
+        chart: Option<PathBuf>,
+        /// Only chart the N most common options (--bars sums the rest into "Other")
+        #[arg(long)]
+        top: Option<usize>,
+        /// Write a Vega-Lite spec of the distribution to this JSON file
+        #[arg(long)]
+        vega: Option<PathBuf>,
+    },
+    /// Compare two subsets on the same question with significance testing
+    Compare {
+        /// Question ID or column name to compare on
+        question: QuestionRef,
+        /// First subset as QUESTION:OPTION (e.g. RemoteWork:Remote)
+        #[arg(long, value_parser = parse_subset_spec)]
+        left: (QuestionRef, String),
+        /// Second subset as QUESTION:OPTION (e.g. RemoteWork:In-person)
+        #[arg(long, value_parser = parse_subset_spec)]
+        right: (QuestionRef, String),
+        /// Multiple-comparison correction: none, holm or bh
+        #[arg(long, default_value = "holm")]
+        correction: Correction,
+        /// Significance level
+        #[arg(long, default_value = "0.05")]
+        alpha: f64,
+    },
+    /// Bootstrap a confidence interval for the mean or median of a numeric question
+    Bootstrap {
+        /// Numeric question ID or column name
+        question: QuestionRef,
+        /// Statistic to bootstrap: mean or median
+        #[arg(short, long, default_value = "median")]
+        statistic: NumericStatistic,
+        /// Restrict to a subset given as QUESTION:OPTION
+        #[arg(long, value_parser = parse_subset_spec)]
+        subset: Option<(QuestionRef, String)>,
+        /// Number of resamples
+        #[arg(short, long, default_value = "1000")]
+        resamples: usize,
+        /// Random seed
+        #[arg(long, default_value = "0")]
+        seed: u64,
+        /// Confidence level
+        #[arg(long, default_value = "0.95")]
+        level: f64,
+        /// Interval method: percentile or bca
+        #[arg(short, long, default_value = "percentile")]
+        method: BootstrapMethod,
+    },
+    /// Analyze free-text answers: word/bigram frequencies, keywords and keyword-in-context
+    Text {
+        /// Text question ID or column name
+        question: QuestionRef,
+        /// Number of words, bigrams or keywords to show
+        #[arg(short, long, default_value = "20")]
+        top: usize,
+        /// Restrict to a subset given as QUESTION:OPTION
+        #[arg(long, value_parser = parse_subset_spec)]
+        subset: Option<(QuestionRef, String)>,
+        /// Show TF-IDF keywords of the subset instead of raw frequencies
+        #[arg(short, long, requires = "subset")]
+        keywords: bool,
+        /// Show every occurrence of this keyword with surrounding context
+        #[arg(long, conflicts_with = "keywords")]
+        kwic: Option<String>,
+        /// Characters of context on each side for --kwic
+        #[arg(short, long, default_value = "40")]
+        width: usize,
+    },
+    /// Cross-tabulate two choice questions
+    Crosstab {
+        /// Row question ID or column name
+        row_question: QuestionRef,
+        /// Column question ID or column name
+        column_question: QuestionRef,
+        /// Write a stacked bar chart of the row percentages to this SVG file
+        #[arg(long)]
+        chart: Option<PathBuf>,
+        /// Only chart the N largest rows
+        #[arg(long)]
+        top: Option<usize>,
+        /// Write a Vega-Lite spec of the row percentages to this JSON file
+        #[arg(long)]
+        vega: Option<PathBuf>,
+    },
+    /// Display summary statistics for a numeric question
+    Summary {
+        /// Question ID or column name (e.g. LanguageHaveWorkedWith)
+        question: QuestionRef,
+        /// Write a histogram of the answers to this SVG file
+        #[arg(long)]
+        chart: Option<PathBuf>,
+        /// Write a Vega-Lite histogram spec to this JSON file
+        #[arg(long)]
+        vega: Option<PathBuf>,
+        /// Number of histogram bins (default: Sturges' rule)
+        #[arg(long)]
+        bins: Option<usize>,
+    },
+    /// Display distributions for a question group, or a subset across the group
+    Group {
+        /// Group name (e.g. Language)
+        name: String,
+        /// Create a subset of respondents who chose this option in any question of the group
+        #[arg(short, long)]
+        option: Option<String>,
+    },
+    /// Display admired/desired statistics for "worked with" / "want to work with" families
+    Admired {
+        /// Question family (e.g. Language); lists all families if omitted
+        family: Option<String>,
+        /// Number of migration flows to show
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+    },
+    /// Export the original columns of a subset's respondents to a .csv or .xlsx file
+    Export {
+        /// Subset given as QUESTION:OPTION
+        #[arg(value_parser = parse_subset_spec)]
+        subset: (QuestionRef, String),
+        /// Output file; the extension (.csv or .xlsx) picks the format
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Comma-separated columns to export, by ID or name (default: all columns)
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<QuestionRef>,
+    },
+    /// Render a Markdown or HTML report from a JSON spec of sections
+    Report {
+        /// Report spec file (JSON with a title and a list of sections)
+        spec: PathBuf,
+        /// Output file; .md or .html picks the format (default: Markdown on stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Document format, overriding the output file extension: markdown or html
+        #[arg(long)]
+        to: Option<ReportFormat>,
+    },
+    /// Interactive REPL mode
+    Repl {
+        /// Reopen a saved workspace: its data file, load options, subsets and variables
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Open the workspace even if its data files have changed
+        #[arg(long, requires = "workspace")]
+        force: bool,
+    },
+    /// Re-run a saved workspace's commands against its recorded data file
+    Replay {
+        /// Workspace file written by the REPL `save` command
+        workspace: PathBuf,
+        /// Replay even if the data files have changed since the workspace was saved
+        #[arg(long)]
+        force: bool,
+        /// Stop at the first failing command
+        #[arg(long)]
+        fail_fast: bool,
+    },
+    /// Serve a local HTTP JSON API over the loaded survey
+    Serve {
+        /// Address to bind; keep the default to accept local connections only
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on (0 picks a free port)
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Run REPL commands from a script file, or from stdin when no file is given
+    Run {
+        /// Script with one REPL command per line ('#' starts a comment line)
+        script: Option<PathBuf>,
+        /// Stop at the first failing command
+        #[arg(long)]
+        fail_fast: bool,
+        /// Define a variable for $NAME substitution, as NAME=VALUE (repeatable)
+        #[arg(long = "var", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), SurveyError> {
+    let format = cli.format;
+    // These commands print no result table, so a JSON or CSV request would be silently dropped
+    let unformatted = match &cli.command {
+        Commands::Repl { .. } => Some("repl"),
+        Commands::Replay { .. } => Some("replay"),
+        Commands::Run { .. } => Some("run"),
+        Commands::Report { .. } => Some("report"),
+        Commands::Export { .. } => Some("export"),
+        Commands::Serve { .. } => Some("serve"),
+        _ => None,
+    };
+    if let Some(command) = unformatted.filter(|_| !format.is_table()) {
+        return Err(SurveyError::InvalidParameter(format!("--format {} is not supported by '{}'", format, command)));
+    }
+    // These commands work on respondent counts, so weights would be silently dropped
+    let unweighted = match &cli.command {
+        Commands::Subset { .. } => Some("subset"),
+        Commands::Compare { .. } => Some("compare"),
+        Commands::Bootstrap { .. } => Some("bootstrap"),
+        Commands::Text { .. } => Some("text"),
+        Commands::Group { .. } => Some("group"),
+        Commands::Admired { .. } => Some("admired"),
+        _ => None,
+    };
+    if let Some(command) = unweighted.filter(|_| cli.weight_column.is_some() || cli.rake.is_some()) {
+        return Err(SurveyError::InvalidParameter(format!(
+            "'{}' does not support weighting (--weight-column or --rake)",
+            command
+        )));
+    }
+
+    // A reopened workspace decides which data file is loaded and how
+    let workspace = match &cli.command {
+        Commands::Repl { workspace: Some(path), force } | Commands::Replay { workspace: path, force, .. } => {
+            Some(open_workspace(path, *force)?)
+        }
+        _ => None,
+    };
+    let (file, weight_column, rake) = match &workspace {
+        Some(workspace) => (
+            workspace.data.path.clone(),
+            workspace.options.weight_column.clone(),
+            workspace.options.rake.as_ref().map(|rake| rake.path.clone()),
+        ),
+        None => (cli.file.clone(), cli.weight_column.clone(), cli.rake.clone()),
+    };
+
+    eprintln!("Loading survey data from: {:?}", file);
+    let analyzer = SurveyAnalyzer::from_path(&file)?;
+    eprintln!("Loaded {} questions with {} total respondents\n",
+              analyzer.get_survey_structure().len(),
+              analyzer.survey().respondent_count());
+
+    let weights = load_weights(&analyzer, weight_column.clone(), rake.as_deref())?;
+    // What a session records on `save`: the reopened workspace, or the files given on the command line
+    let inputs = match workspace {
+        Some(workspace) => session::Inputs::Opened(workspace),
+        None => session::Inputs::Files { data: file.clone(), weight_column, rake },
+    };
+    if let Some(weights) = &weights {
+        eprintln!("Weighting respondents (effective sample size {:.1})\n",
+                  weights.effective_sample_size(0..analyzer.survey().respondent_count()));
+    }
+
+    match cli.command {
+        Commands::Structure { limit, filter, grouped } => {
+            let questions = analyzer.get_survey_structure();
+            let mut filtered_questions: Vec<_> = questions.iter().collect();
+
+            if let Some(filter_term) = filter {
+                filtered_questions = analyzer.search_questions(&filter_term);
+            }
+
+            if grouped {
+                if format.is_table() {
+                    print_grouped_structure(&analyzer, &filtered_questions, limit);
+                } else {
+                    let groups: Vec<_> = analyzer
+                        .get_question_groups()
+                        .iter()
+                        .filter(|group| filtered_questions.iter().any(|q| group.contains(q.id)))
+                        .take(limit.unwrap_or(usize::MAX))
+                        .collect();
+                    output::emit(format, &groups)?;
+                }
+                return Ok(());
+            }
+
+            if let Some(limit) = limit {
+                filtered_questions.truncate(limit);
+            }
+
+            if !format.is_table() {
+                return output::emit(format, &filtered_questions);
+            }
+
+            println!("Survey Structure ({} questions):", filtered_questions.len());
+            println!("{:-<80}", "");
+            
+            for question in filtered_questions {
+                println!("Question {}: {}", question.id, question.text);
+                println!("  Type: {:?}", question.question_type);
+                if !question.options.is_empty() {
+                    println!("  Options: {}", question.options.join(", "));
+                }
+                println!();
+            }
+        }
+
+        Commands::Search { term, options, regex, fuzzy, ranked, limit } => {
+            let mode = if regex {
+                SearchMode::Regex
+            } else if fuzzy {
+                SearchMode::Fuzzy
+            } else {
+                SearchMode::Substring
+            };
+
+            if mode != SearchMode::Substring {
+                let mut results = if options {
+                    analyzer.search_options_with(&term, mode)?
+                } else {
+                    analyzer.search_questions_with(&term, mode)?
+                };
+                if !format.is_table() {
+                    results.truncate(limit);
+                    return output::emit(format, &results);
+                }
+                let kind = if options { "option(s)" } else { "question(s)" };
+                println!("Found {} {} matching '{}' ({}):", results.len(), kind, term, mode);
+                print_search_matches(&mut std::io::stdout(), &results, limit, std::io::stdout().is_terminal())?;
+            } else if ranked {
+                let hits = analyzer.search(&term, limit);
+                if !format.is_table() {
+                    return output::emit(format, &hits);
+                }
+                println!("Found {} result(s) for '{}':", hits.len(), term);
+                for hit in hits {
+                    println!("  {}", hit.display());
+                }
+            } else {
+                let (hits, kind) = if options {
+                    (analyzer.search_options_ranked(&term, limit), "option(s)")
+                } else {
+                    (analyzer.search_questions_ranked(&term, limit), "question(s)")
+                };
+                if !format.is_table() {
+                    return output::emit(format, &hits);
+                }
+                println!("Found {} {} matching '{}':", hits.len(), kind, term);
+                for hit in hits {
+                    match hit.target {
+                        SearchTarget::Question { question_id } | SearchTarget::Option { question_id, .. } => {
+                            println!("  Question {}: {}", question_id, hit.text);
+                        }
+                        SearchTarget::TextAnswer { .. } => {}
+                    }
+                }
+            }
+        }
+
+        Commands::Subset { question, option } => {
+            let subset = analyzer.create_subset(question, &option)?;
+            if !format.is_table() {
+                return output::emit(format, &subset);
+            }
+            println!("{}", subset.display());
+        }
+
+        Commands::Distribution { question, threshold, ci, ci_method, bars, chart, top, vega } => {
+            let top = top.unwrap_or(usize::MAX);
+            if let Some(weights) = &weights {
+                if ci.is_some() {
+                    return Err(SurveyError::InvalidParameter(
+                        "Confidence intervals are not available for weighted data".to_string(),
+                    ));
+                }
+                let mut distribution = analyzer.get_weighted_distribution(question, weights)?;
+                if let Some(path) = &chart {
+                    write_chart(path, &BarChart::from_weighted_distribution(&distribution).top(top).to_svg())?;
+                }
+                if let Some(path) = &vega {
+                    write_chart(path, &format!("{:#}\n", distribution.to_vega_lite()))?;
+                }
+                if !format.is_table() {
+                    distribution.distribution.retain(|_, (_, percentage)| *percentage >= threshold);
+                    return output::emit(format, &distribution);
+                }
+                if bars {
+                    let chart = BarChart::from_weighted_distribution(&distribution).top_with_other(top);
+                    println!("Total weight: {:.1} ({} responses)\n", distribution.total_weight, distribution.total_responses);
+                    println!("{}", chart.to_text(terminal_width()));
+                } else {
+                    println!("{}", distribution.display());
+                }
+
+                if threshold > 0.0 {
+                    println!("\nAnswers above {:.1}% threshold:", threshold);
+                    for (option, (weight, percentage)) in &distribution.distribution {
+                        if *percentage >= threshold {
+                            println!("  {}: {:.1} ({:.1}%)", option, weight, percentage);
+                        }
+                    }
+                }
+                return Ok(());
+            }
+
+            let mut distribution = match ci {
+                Some(level) => analyzer.get_distribution_with_intervals(question, level, ci_method)?,
+                None => analyzer.get_distribution(question)?,
+            };
+            if let Some(path) = &chart {
+                write_chart(path, &BarChart::from_distribution(&distribution).top(top).to_svg())?;
+            }
+            if let Some(path) = &vega {
+                write_chart(path, &format!("{:#}\n", distribution.to_vega_lite()))?;
+            }
+            if !format.is_table() {
+                distribution.distribution.retain(|_, (_, percentage)| *percentage >= threshold);
+                return output::emit(format, &distribution);
+            }
+            if bars {
+                let chart = BarChart::from_distribution(&distribution).top_with_other(top);
+                println!("Total responses: {}\n", distribution.total_responses);
+                println!("{}", chart.to_text(terminal_width()));
+            } else {
+                println!("{}", distribution.display());
+            }
+
+            if threshold > 0.0 {
+                let above_threshold = distribution.above_threshold(threshold);
+                if !above_threshold.is_empty() {
+                    println!("\nAnswers above {:.1}% threshold:", threshold);
+                    for (option, count, percentage) in above_threshold {
+                        println!("  {}: {} ({:.1}%)", option, count, percentage);
+                    }
+                }
+            }
+        }
+
+        Commands::Compare { question, left, right, correction, alpha } => {
+            let left = analyzer.create_subset(left.0, &left.1)?;
+            let right = analyzer.create_subset(right.0, &right.1)?;
+            let comparison = analyzer.compare_with(question, &left, &right, correction, alpha)?;
+            if !format.is_table() {
+                return output::emit(format, &comparison);
+            }
+            println!("{}", comparison.display());
+        }
+
+        Commands::Bootstrap { question, statistic, subset, resamples, seed, level, method } => {
+            let subset = subset
+                .map(|(subset_question, option)| analyzer.create_subset(subset_question, &option))
+                .transpose()?;
+            let bootstrap = Bootstrap { resamples, seed, level, method, ..Bootstrap::default() };
+            let result = analyzer.bootstrap_numeric(&question, subset.as_ref(), statistic, &bootstrap)?;
+            if !format.is_table() {
+                return output::emit(format, &result);
+            }
+            println!("Question {}: {:?}", question, statistic);
+            println!("{}", result.display());
+        }
+
+        Commands::Text { question, top, subset, keywords, kwic, width } => {
+            let subset = subset
+                .map(|(subset_question, option)| analyzer.create_subset(subset_question, &option))
+                .transpose()?;
+
+            if let Some(keyword) = kwic {
+                let hits = analyzer.keyword_in_context(question, &keyword, width, subset.as_ref())?;
+                if !format.is_table() {
+                    return output::emit(format, &hits);
+                }
+                println!("Found {} occurrence(s) of '{}':", hits.len(), keyword);
+                for hit in hits {
+                    println!("{}", hit.display(width));
+                }
+            } else if let (true, Some(subset)) = (keywords, &subset) {
+                let keywords = analyzer.get_text_keywords(question, subset, top)?;
+                if !format.is_table() {
+                    return output::emit(format, &keywords);
+                }
+                println!("Keywords for '{}' ({} respondents):", subset.option, subset.size());
+                for keyword in keywords {
+                    println!("  {}: {:.4} ({} occurrences)", keyword.term, keyword.tf_idf, keyword.count);
+                }
+            } else {
+                let mut analysis = analyzer.get_text_analysis(question, subset.as_ref())?;
+                if !format.is_table() {
+                    analysis.words.truncate(top);
+                    analysis.bigrams.truncate(top);
+                    return output::emit(format, &analysis);
+                }
+                println!("{}", analysis.display(top));
+            }
+        }
+
+        Commands::Crosstab { row_question, column_question, chart, top, vega } => {
+            let crosstab = analyzer.get_cross_tab(row_question, column_question, weights.as_ref())?;
+            if let Some(path) = &chart {
+                let chart = StackedBarChart::from_cross_tab(&crosstab).top(top.unwrap_or(usize::MAX));
+                write_chart(path, &chart.to_svg())?;
+            }
+            if let Some(path) = &vega {
+                write_chart(path, &format!("{:#}\n", crosstab.to_vega_lite()))?;
+            }
+            if !format.is_table() {
+                return output::emit(format, &crosstab);
+            }
+            println!("{}", crosstab.display());
+        }
+
+        Commands::Summary { question, chart, vega, bins } => {
+            let summary = analyzer.get_numeric_summary(&question, weights.as_ref())?;
+            if chart.is_some() || vega.is_some() {
+                let histogram = analyzer.get_histogram(question, bins, weights.as_ref())?;
+                if let Some(path) = &chart {
+                    write_chart(path, &histogram.to_svg())?;
+                }
+                if let Some(path) = &vega {
+                    write_chart(path, &format!("{:#}\n", histogram.to_vega_lite()))?;
+                }
+            }
+            if !format.is_table() {
+                return output::emit(format, &summary);
+            }
+            println!("{}", summary.display());
+        }
+
+        Commands::Group { name, option } => {
+            match option {
+                Some(option) => {
+                    let subset = analyzer.create_group_subset(&name, &option)?;
+                    if !format.is_table() {
+                        return output::emit(format, &subset);
+                    }
+                    println!("{}", subset.display());
+                }
+                None => {
+                    let distributions = analyzer.get_group_distributions(&name)?;
+                    if !format.is_table() {
+                        return output::emit(format, &distributions);
+                    }
+                    for distribution in distributions {
+                        println!("{}", distribution.display());
+                    }
+                }
+            }
+        }
+
+        Commands::Admired { family, top } => {
+            let pairs = analyzer.survey().worked_with_pairs();
+
+            match family {
+                Some(family) => {
+                    let (_, have_id, want_id) = pairs
+                        .into_iter()
+                        .find(|(name, _, _)| name.eq_ignore_ascii_case(&family))
+                        .ok_or(SurveyError::GroupNotFound(family))?;
+                    let mut result = analyzer.get_admired_desired(have_id, want_id)?;
+                    if !format.is_table() {
+                        result.migrations.truncate(top);
+                        return output::emit(format, &result);
+                    }
+                    println!("{}", result.display(top));
+                }
+                None => {
+                    if !format.is_table() {
+                        let families: Vec<Family> = pairs
+                            .into_iter()
+                            .map(|(family, have_question_id, want_question_id)| Family { family, have_question_id, want_question_id })
+                            .collect();
+                        return output::emit(format, &families);
+                    }
+                    println!("Found {} question families:", pairs.len());
+                    for (family, have_id, want_id) in pairs {
+                        println!("  {}: Questions {} and {}", family, have_id, want_id);
+                    }
+                }
+            }
+        }
+
+        Commands::Export { subset, output, columns } => {
+            let subset = analyzer.create_subset(subset.0, &subset.1)?;
+            let rows = analyzer.export_rows(&subset, &columns, &output)?;
+            eprintln!("Exported {} row(s) to {}", rows, output.display());
+        }
+
+        Commands::Report { spec, output, to } => {
+            let report = Report::from_file(&spec)?;
+            let format = match (to, &output) {
+                (Some(format), _) => format,
+                (None, Some(path)) => ReportFormat::from_path(path)?,
+                (None, None) => ReportFormat::Markdown,
+            };
+            let document = analyzer.render_report(&report, format, weights.as_ref())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, document)?;
+                    eprintln!("Wrote {} report with {} section(s) to {}", format, report.sections.len(), path.display());
+                }
+                None => print!("{}", document),
+            }
+        }
+
+        Commands::Repl { .. } => {
+            let mut session = session::Session::new(&analyzer, weights.as_ref(), std::io::stdout().is_terminal())
+                .with_inputs(inputs);
+            if let Some(restored) = session.restore()? {
+                eprintln!("Restored {} subset(s) from the workspace\n", restored);
+            }
+            repl::run_repl(&analyzer, session)?;
+        }
+
+        Commands::Replay { workspace: path, fail_fast, .. } => {
+            let mut session = session::Session::new(&analyzer, weights.as_ref(), false).with_inputs(inputs);
+            session::replay(&mut session, &path.display().to_string(), fail_fast)?;
+        }
+
+        Commands::Serve { host, port } => {
+            serve::serve(&analyzer, weights.as_ref(), &format!("{}:{}", host, port))?;
+        }
+
+        Commands::Run { script, fail_fast, variables } => {
+            let mut session = session::Session::new(&analyzer, weights.as_ref(), false).with_inputs(inputs);
+            for (name, value) in &variables {
+                session.set_variable(name, value)?;
+            }
+            match script.filter(|path| path.as_os_str() != "-") {
+                Some(path) => {
+                    let reader = std::io::BufReader::new(std::fs::File::open(&path)?);
+                    session::run_script(&mut session, reader, &path.display().to_string(), fail_fast)?;
+                }
+                None => session::run_script(&mut session, std::io::stdin().lock(), "<stdin>", fail_fast)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Markers around highlighted matches: ANSI bold yellow on a terminal, brackets otherwise
+fn highlight_markers(color: bool) -> (&'static str, &'static str) {
+    if color && std::env::var_os("NO_COLOR").is_none() {
+        ("\x1b[1;33m", "\x1b[0m")
+    } else {
+        ("[", "]")
+    }
+}
+
+/// List scored matches, highlighting matched text in color or with brackets
+fn print_search_matches(out: &mut dyn Write, results: &[SearchMatch], limit: usize, color: bool) -> std::io::Result<()> {
+    let (open, close) = highlight_markers(color);
+    writeln!(out, "  {:>5}  Match", "Score")?;
+    for result in results.iter().take(limit) {
+        writeln!(out, "  {:>5.2}  Q{}: {}", result.score, result.question_id, result.highlighted(open, close))?;
+    }
+    if results.len() > limit {
+        writeln!(out, "  ... {} more", results.len() - limit)?;
+    }
+    Ok(())
+}
+
+/// Split REPL search arguments into an optional `--regex` / `--fuzzy` flag and the term
+fn parse_search_args(args: &[&str]) -> (SearchMode, String) {
+    match args.first() {
+        Some(&"--regex") => (SearchMode::Regex, args[1..].join(" ")),
+        Some(&"--fuzzy") => (SearchMode::Fuzzy, args[1..].join(" ")),
+        _ => (SearchMode::Substring, args.join(" ")),
+    }
+}
+
+/// Parse a `NAME=VALUE` script variable
+fn parse_variable(spec: &str) -> Result<(String, String), String> {
+    let (name, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("Expected NAME=VALUE, got '{}'", spec))?;
+    Ok((name.trim().to_string(), value.to_string()))
+}
+
+/// Parse a `QUESTION:OPTION` subset specification (question by ID or column name)
+fn parse_subset_spec(spec: &str) -> Result<(QuestionRef, String), String> {
+    let (question, option) = spec
+        .split_once(':')
+        .ok_or_else(|| format!("Expected QUESTION:OPTION, got '{}'", spec))?;
+    Ok((question.parse()?, option.trim().to_string()))
+}
+
+/// Open a workspace file, checking its data files are unchanged unless forced
+fn open_workspace(path: &Path, force: bool) -> Result<Workspace, SurveyError> {
+    let workspace = Workspace::open(path)?;
+    match workspace.verify() {
+        Err(e @ SurveyError::DataFileChanged { .. }) if force => eprintln!("Warning: {}", e),
+        result => result?,
+    }
+    Ok(workspace)
+}
+
+fn load_weights(analyzer: &SurveyAnalyzer, weight_column: Option<QuestionRef>, rake: Option<&Path>) -> Result<Option<Weights>, SurveyError> {
+    if let Some(question) = weight_column {
+        return analyzer.weights_from_column(question).map(Some);
+    }
+
+    match rake {
+        Some(path) => {
+            let targets: Vec<RakeTarget> = serde_json::from_reader(std::fs::File::open(path)?)
+                .map_err(|e| SurveyError::DataParsingError(format!("Invalid raking targets: {}", e)))?;
+            analyzer.rake_weights(&targets, RAKING_ITERATIONS).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+fn print_grouped_structure(analyzer: &SurveyAnalyzer, questions: &[&Question], limit: Option<usize>) {
+    let groups: Vec<_> = analyzer
+        .get_question_groups()
+        .iter()
+        .filter(|group| questions.iter().any(|q| group.contains(q.id)))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    println!("Survey Structure ({} question groups):", groups.len());
+    println!("{:-<80}", "");
+
+    for group in &groups {
+        println!("Group {} ({} questions):", group.name, group.question_ids.len());
+        for question in questions.iter().filter(|q| group.contains(q.id)) {
+            println!("  Question {}: {} ({:?})", question.id, question.text, question.question_type);
+        }
+        println!();
+    }
+
+    let ungrouped: Vec<_> = questions
+        .iter()
+        .filter(|q| !analyzer.get_question_groups().iter().any(|g| g.contains(q.id)))
+        .collect();
+    if limit.is_none() && !ungrouped.is_empty() {
+        println!("Ungrouped ({} questions):", ungrouped.len());
+        for question in ungrouped {
+            println!("  Question {}: {} ({:?})", question.id, question.text, question.question_type);
+        }
+    }
+}
+
+/// Terminal width from `COLUMNS`, falling back to 80 columns
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+}
+
+/// Save an SVG chart or Vega-Lite spec, reporting the path on stderr so stdout stays clean
+fn write_chart(path: &Path, svg: &str) -> Result<(), SurveyError> {
+    std::fs::write(path, svg)?;
+    eprintln!("Wrote chart to {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,480 @@
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+use serde::Serialize;
+use so_survey_analyzer::{
+    AdmiredDesired, AnswerDistribution, BootstrapResult, CrossTab, Keyword, KeywordInContext, NumericSummary,
+    Question, QuestionGroup, SearchHit, SearchMatch, SearchTarget, Subset, SubsetComparison, SurveyError,
+    TextAnalysis, WeightedDistribution,
+};
+
+/// How subcommand results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl OutputFormat {
+    /// Check if results are printed as human-readable text
+    pub fn is_table(&self) -> bool {
+        matches!(self, OutputFormat::Table)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("Unknown output format: {} (expected table, json or csv)", s)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Table => write!(f, "table"),
+            OutputFormat::Json => write!(f, "json"),
+            OutputFormat::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// A result that can be flattened into CSV rows
+pub trait Records {
+    const HEADERS: &'static [&'static str];
+
+    fn records(&self) -> Vec<Vec<String>>;
+}
+
+impl<T: Records> Records for Vec<T> {
+    const HEADERS: &'static [&'static str] = T::HEADERS;
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.iter().flat_map(Records::records).collect()
+    }
+}
+
+impl<T: Records> Records for &T {
+    const HEADERS: &'static [&'static str] = T::HEADERS;
+
+    fn records(&self) -> Vec<Vec<String>> {
+        (*self).records()
+    }
+}
+
+/// Write a result to stdout as JSON or CSV (table output is printed by each subcommand)
+pub fn emit<T: Serialize + Records>(format: OutputFormat, value: &T) -> Result<(), SurveyError> {
+    write_to(std::io::stdout().lock(), format, value)
+}
+
+fn write_to<W: Write, T: Serialize + Records>(mut out: W, format: OutputFormat, value: &T) -> Result<(), SurveyError> {
+    match format {
+        OutputFormat::Table => Ok(()),
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(value)
+                .map_err(|e| SurveyError::DataParsingError(format!("Could not serialize output: {}", e)))?;
+            writeln!(out, "{}", json)?;
+            Ok(())
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(T::HEADERS)?;
+            for record in value.records() {
+                writer.write_record(&record)?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+    }
+}
+
+/// A `*HaveWorkedWith` / `*WantToWorkWith` question family
+#[derive(Serialize)]
+pub struct Family {
+    pub family: String,
+    pub have_question_id: usize,
+    pub want_question_id: usize,
+}
+
+impl Records for Question {
+    const HEADERS: &'static [&'static str] = &["question_id", "text", "type", "options"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.id.to_string(),
+            self.text.clone(),
+            format!("{:?}", self.question_type),
+            self.options.join(";"),
+        ]]
+    }
+}
+
+impl Records for QuestionGroup {
+    const HEADERS: &'static [&'static str] = &["group", "question_ids"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let ids: Vec<String> = self.question_ids.iter().map(usize::to_string).collect();
+        vec![vec![self.name.clone(), ids.join(";")]]
+    }
+}
+
+impl Records for Family {
+    const HEADERS: &'static [&'static str] = &["family", "have_question_id", "want_question_id"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![self.family.clone(), self.have_question_id.to_string(), self.want_question_id.to_string()]]
+    }
+}
+
+impl Records for SearchHit {
+    const HEADERS: &'static [&'static str] = &["target", "question_id", "detail", "text", "score"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let (target, question_id, detail) = match &self.target {
+            SearchTarget::Question { question_id } => ("question", question_id, String::new()),
+            SearchTarget::Option { question_id, option } => ("option", question_id, option.clone()),
+            SearchTarget::TextAnswer { question_id, respondent_id } => ("answer", question_id, respondent_id.to_string()),
+        };
+        vec![vec![target.to_string(), question_id.to_string(), detail, self.text.clone(), self.score.to_string()]]
+    }
+}
+
+impl Records for SearchMatch {
+    const HEADERS: &'static [&'static str] = &["question_id", "option", "text", "score"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.question_id.to_string(),
+            self.option.clone().unwrap_or_default(),
+            self.text.clone(),
+            self.score.to_string(),
+        ]]
+    }
+}
+
+impl Records for Subset {
+    const HEADERS: &'static [&'static str] = &["question_id", "group", "option", "size", "total_respondents", "percentage"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.question_id.to_string(),
+            self.group.clone().unwrap_or_default(),
+            self.option.clone(),
+            self.size().to_string(),
+            self.total_respondents.to_string(),
+            self.percentage().to_string(),
+        ]]
+    }
+}
+
+impl Records for AnswerDistribution {
+    const HEADERS: &'static [&'static str] =
+        &["question_id", "question_text", "option", "count", "percentage", "ci_lower", "ci_upper"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let mut options: Vec<_> = self.distribution.iter().collect();
+        options.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+        options
+            .into_iter()
+            .map(|(option, (count, percentage))| {
+                let interval = self.intervals.get(option);
+                vec![
+                    self.question_id.to_string(),
+                    self.question_text.clone(),
+                    option.clone(),
+                    count.to_string(),
+                    percentage.to_string(),
+                    interval.map(|ci| ci.lower.to_string()).unwrap_or_default(),
+                    interval.map(|ci| ci.upper.to_string()).unwrap_or_default(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Records for WeightedDistribution {
+    const HEADERS: &'static [&'static str] = &["question_id", "question_text", "option", "weight", "percentage"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let mut options: Vec<_> = self.distribution.iter().collect();
+        options.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+        options
+            .into_iter()
+            .map(|(option, (weight, percentage))| {
+                vec![
+                    self.question_id.to_string(),
+                    self.question_text.clone(),
+                    option.clone(),
+                    weight.to_string(),
+                    percentage.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Records for SubsetComparison {
+    const HEADERS: &'static [&'static str] = &[
+        "question_id", "option", "left_count", "left_percentage", "right_count", "right_percentage",
+        "difference", "z_score", "p_value", "adjusted_p_value", "significant",
+    ];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.options
+            .iter()
+            .map(|o| {
+                vec![
+                    self.question_id.to_string(),
+                    o.option.clone(),
+                    o.left_count.to_string(),
+                    o.left_percentage.to_string(),
+                    o.right_count.to_string(),
+                    o.right_percentage.to_string(),
+                    o.difference.to_string(),
+                    o.z_score.to_string(),
+                    o.p_value.to_string(),
+                    o.adjusted_p_value.to_string(),
+                    o.significant.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Records for BootstrapResult {
+    const HEADERS: &'static [&'static str] =
+        &["estimate", "std_error", "lower", "upper", "level", "method", "resamples", "sample_size"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.estimate.to_string(),
+            self.std_error.to_string(),
+            self.lower.to_string(),
+            self.upper.to_string(),
+            self.level.to_string(),
+            self.method.to_string(),
+            self.resamples.to_string(),
+            self.sample_size.to_string(),
+        ]]
+    }
+}
+
+impl Records for TextAnalysis {
+    const HEADERS: &'static [&'static str] = &["kind", "term", "count"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let words = self.words.iter().map(|(term, count)| ("word", term, count));
+        let bigrams = self.bigrams.iter().map(|(term, count)| ("bigram", term, count));
+        words
+            .chain(bigrams)
+            .map(|(kind, term, count)| vec![kind.to_string(), term.clone(), count.to_string()])
+            .collect()
+    }
+}
+
+impl Records for Keyword {
+    const HEADERS: &'static [&'static str] = &["term", "count", "documents", "tf_idf"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![self.term.clone(), self.count.to_string(), self.documents.to_string(), self.tf_idf.to_string()]]
+    }
+}
+
+impl Records for KeywordInContext {
+    const HEADERS: &'static [&'static str] = &["respondent_id", "left", "keyword", "right"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![self.respondent_id.to_string(), self.left.clone(), self.keyword.clone(), self.right.clone()]]
+    }
+}
+
+impl Records for CrossTab {
+    const HEADERS: &'static [&'static str] = &["row_option", "column_option", "count", "row_percentage"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        let percentages = self.row_percentages();
+        let mut records = Vec::new();
+        for (r, row_option) in self.row_options.iter().enumerate() {
+            for (c, column_option) in self.column_options.iter().enumerate() {
+                records.push(vec![
+                    row_option.clone(),
+                    column_option.clone(),
+                    self.counts[r][c].to_string(),
+                    percentages[r][c].to_string(),
+                ]);
+            }
+        }
+        records
+    }
+}
+
+impl Records for NumericSummary {
+    const HEADERS: &'static [&'static str] = &[
+        "question_id", "question_text", "count", "total_weight", "mean", "std_dev", "min", "median", "max",
+        "effective_sample_size",
+    ];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.question_id.to_string(),
+            self.question_text.clone(),
+            self.count.to_string(),
+            self.total_weight.to_string(),
+            self.mean.to_string(),
+            self.std_dev.to_string(),
+            self.min.to_string(),
+            self.median.to_string(),
+            self.max.to_string(),
+            self.effective_sample_size.to_string(),
+        ]]
+    }
+}
+
+impl Records for AdmiredDesired {
+    const HEADERS: &'static [&'static str] =
+        &["family", "option", "worked_with", "want_to_work_with", "continuing", "admired", "desired"];
+
+    fn records(&self) -> Vec<Vec<String>> {
+        self.options
+            .iter()
+            .map(|o| {
+                vec![
+                    self.family.clone(),
+                    o.option.clone(),
+                    o.worked_with.to_string(),
+                    o.want_to_work_with.to_string(),
+                    o.continuing.to_string(),
+                    o.admired.to_string(),
+                    o.desired.to_string(),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use so_survey_analyzer::{ConfidenceInterval, Correction, IntervalMethod, OptionComparison, QuestionType};
+
+    fn distribution() -> AnswerDistribution {
+        AnswerDistribution {
+            question_id: 3,
+            question_text: "Remote work".to_string(),
+            question_type: QuestionType::SingleChoice,
+            distribution: HashMap::from([
+                ("Hybrid".to_string(), (2, 20.0)),
+                ("Remote".to_string(), (5, 50.0)),
+                ("In-person".to_string(), (3, 30.0)),
+            ]),
+            total_responses: 10,
+            intervals: HashMap::from([(
+                "Remote".to_string(),
+                ConfidenceInterval { lower: 0.2, upper: 0.8, level: 0.95, method: IntervalMethod::Wilson },
+            )]),
+        }
+    }
+
+    fn csv_output<T: Serialize + Records>(value: &T) -> String {
+        let mut out = Vec::new();
+        write_to(&mut out, OutputFormat::Csv, value).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_distribution_records() {
+        let records = distribution().records();
+        let options: Vec<_> = records.iter().map(|r| r[2].as_str()).collect();
+        assert_eq!(options, vec!["Remote", "In-person", "Hybrid"]);
+        assert_eq!(records[0], vec!["3", "Remote work", "Remote", "5", "50", "0.2", "0.8"]);
+        assert_eq!(records[1][5..], ["", ""]);
+        assert!(records.iter().all(|r| r.len() == AnswerDistribution::HEADERS.len()));
+    }
+
+    #[test]
+    fn test_crosstab_records() {
+        let crosstab = CrossTab {
+            row_question_id: 1,
+            row_question_text: "Country".to_string(),
+            column_question_id: 3,
+            column_question_text: "Remote work".to_string(),
+            row_options: vec!["Germany".to_string(), "India".to_string()],
+            column_options: vec!["Remote".to_string(), "Hybrid".to_string()],
+            counts: vec![vec![3.0, 1.0], vec![0.0, 2.0]],
+            total_responses: 6,
+            effective_sample_size: 6.0,
+            weighted: false,
+        };
+        let records = crosstab.records();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0], vec!["Germany", "Remote", "3", "75"]);
+        assert_eq!(records[3], vec!["India", "Hybrid", "2", "100"]);
+    }
+
+    #[test]
+    fn test_comparison_records() {
+        let comparison = SubsetComparison {
+            question_id: 3,
+            question_text: "Remote work".to_string(),
+            left_label: "Germany".to_string(),
+            right_label: "India".to_string(),
+            left_total: 4,
+            right_total: 2,
+            correction: Correction::Holm,
+            alpha: 0.05,
+            options: vec![OptionComparison {
+                option: "Remote".to_string(),
+                left_count: 3,
+                left_percentage: 75.0,
+                right_count: 0,
+                right_percentage: 0.0,
+                difference: 75.0,
+                z_score: 2.1,
+                p_value: 0.03,
+                adjusted_p_value: 0.06,
+                significant: false,
+            }],
+        };
+        assert_eq!(
+            comparison.records(),
+            vec![vec!["3", "Remote", "3", "75", "0", "0", "75", "2.1", "0.03", "0.06", "false"]]
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let output = csv_output(&distribution());
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("question_id,question_text,option,count,percentage,ci_lower,ci_upper"));
+        assert_eq!(lines.next(), Some("3,Remote work,Remote,5,50,0.2,0.8"));
+        assert_eq!(lines.count(), 2);
+
+        // A Vec writes one header line and the records of every item
+        let output = csv_output(&vec![distribution(), distribution()]);
+        assert_eq!(output.lines().count(), 7);
+        assert_eq!(output.lines().filter(|line| line.starts_with("question_id")).count(), 1);
+    }
+
+    #[test]
+    fn test_write_json_and_table() {
+        let mut out = Vec::new();
+        write_to(&mut out, OutputFormat::Json, &distribution()).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["question_id"], 3);
+        assert_eq!(json["distribution"]["Remote"][0], 5);
+        assert_eq!(json["intervals"]["Remote"]["upper"], 0.8);
+
+        let mut out = Vec::new();
+        write_to(&mut out, OutputFormat::Table, &distribution()).unwrap();
+        assert!(out.is_empty());
+    }
+}
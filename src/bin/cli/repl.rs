@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, Helper};
+use so_survey_analyzer::{SurveyAnalyzer, SurveyError};
+
+use super::session::{write_help, Flow, Session, COMMANDS};
+
+/// History file in the user's home directory
+const HISTORY_FILE: &str = ".so_survey_history";
+
+/// Maximum number of lines kept in the history file
+const HISTORY_SIZE: usize = 1000;
+
+/// Tab completion of commands, question names, the options of the chosen question and saved subsets
+struct ReplHelper<'a> {
+    analyzer: &'a SurveyAnalyzer,
+    subset_names: Vec<String>,
+}
+
+impl ReplHelper<'_> {
+    /// Question column names usable as a single REPL word
+    fn question_candidates(&self, prefix: &str) -> Vec<Pair> {
+        let prefix = prefix.to_lowercase();
+        self.analyzer
+            .get_survey_structure()
+            .iter()
+            .filter(|q| !q.text.contains(char::is_whitespace) && q.text.to_lowercase().starts_with(&prefix))
+            .map(|q| Pair { display: format!("{} ({})", q.text, q.id), replacement: format!("{} ", q.text) })
+            .collect()
+    }
+
+    /// Saved subset names
+    fn subset_candidates(&self, prefix: &str) -> Vec<Pair> {
+        self.subset_names
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair { display: name.clone(), replacement: format!("{} ", name) })
+            .collect()
+    }
+
+    /// Answer options of the question named by `question`
+    fn option_candidates(&self, question: &str, prefix: &str) -> Vec<Pair> {
+        let Ok(question) = self.analyzer.get_question(question) else {
+            return Vec::new();
+        };
+        let prefix = prefix.to_lowercase();
+        question
+            .options
+            .iter()
+            .filter(|option| option.to_lowercase().starts_with(&prefix))
+            .map(|option| Pair { display: option.clone(), replacement: option.clone() })
+            .collect()
+    }
+}
+
+impl Completer for ReplHelper<'_> {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &before[start..];
+        let previous: Vec<&str> = before[..start].split_whitespace().collect();
+
+        let candidates = match previous.as_slice() {
+            [] => COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(word))
+                .map(|command| Pair { display: command.to_string(), replacement: format!("{} ", command) })
+                .collect(),
+            ["search" | "searchopt"] if word.starts_with('-') => ["--regex", "--fuzzy"]
+                .iter()
+                .filter(|flag| flag.starts_with(word))
+                .map(|flag| Pair { display: flag.to_string(), replacement: format!("{} ", flag) })
+                .collect(),
+            ["dist"] if word.starts_with('-') => vec![Pair { display: "--bars".to_string(), replacement: "--bars ".to_string() }],
+            ["dist"] | ["dist", "--bars"] | ["subset"] | ["let", _, "=", "subset"] => self.question_candidates(word),
+            // Options may contain spaces, so complete everything typed after the question
+            ["subset", question, ..] => {
+                let option_start = after_words(before, 2);
+                return Ok((option_start, self.option_candidates(question, &before[option_start..])));
+            }
+            ["let", _, "=", "subset", question, ..] => {
+                let option_start = after_words(before, 5);
+                return Ok((option_start, self.option_candidates(question, &before[option_start..])));
+            }
+            ["let", _, "="] => {
+                let mut candidates = self.subset_candidates(word);
+                if "subset".starts_with(word) {
+                    candidates.insert(0, Pair { display: "subset".to_string(), replacement: "subset ".to_string() });
+                }
+                candidates
+            }
+            ["drop"] => self.subset_candidates(word),
+            [.., "in" | "&" | "|" | "-"] if previous.first().is_some_and(|command| ["dist", "let"].contains(command)) => {
+                self.subset_candidates(word)
+            }
+            _ => Vec::new(),
+        };
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper<'_> {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper<'_> {}
+
+impl Validator for ReplHelper<'_> {}
+
+impl Helper for ReplHelper<'_> {}
+
+/// Byte offset just past the first `n` words of `text` and the whitespace after them
+fn after_words(text: &str, n: usize) -> usize {
+    let mut rest = text;
+    for _ in 0..n {
+        rest = rest.trim_start();
+        rest = &rest[rest.find(char::is_whitespace).unwrap_or(rest.len())..];
+    }
+    text.len() - rest.trim_start().len()
+}
+
+/// History file path, if a home directory is known
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+pub fn run_repl(analyzer: &SurveyAnalyzer, mut session: Session) -> Result<(), SurveyError> {
+    let config = Config::builder()
+        .auto_add_history(true)
+        .history_ignore_dups(true)
+        .and_then(|builder| builder.max_history_size(HISTORY_SIZE))
+        .map_err(readline_error)?
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::with_config(config).map_err(readline_error)?;
+    editor.set_helper(Some(ReplHelper { analyzer, subset_names: session.subset_names() }));
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session
+        let _ = editor.load_history(path);
+    }
+
+    println!("Welcome to the Stack Overflow Survey Analyzer REPL!");
+    write_help(&mut std::io::stdout())?;
+    println!("Tab completes commands, question names, options and saved subsets; Ctrl-R searches history.");
+    println!();
+
+    loop {
+        let input = match editor.readline("survey> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(readline_error(e)),
+        };
+
+        match session.execute(&input, &mut std::io::stdout()) {
+            Ok(Flow::Quit) => break,
+            Ok(Flow::Continue) => {}
+            Err(e) => println!("Error: {}", e),
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.subset_names = session.subset_names();
+        }
+        println!();
+    }
+
+    if let Some(path) = &history {
+        if let Err(e) = editor.save_history(path) {
+            eprintln!("Could not save history to {}: {}", path.display(), e);
+        }
+    }
+    println!("Goodbye!");
+    Ok(())
+}
+
+fn readline_error(error: impl std::fmt::Display) -> SurveyError {
+    SurveyError::Io(std::io::Error::other(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_after_words() {
+        assert_eq!(after_words("subset RemoteWork Hybrid (some", 2), 18);
+        assert_eq!(after_words("  subset   RemoteWork   Hy", 2), 24);
+        assert_eq!(after_words("let r = subset Country United", 5), 23);
+        // Fewer words than asked for ends at the text's end
+        assert_eq!(after_words("subset Country", 3), 14);
+        assert_eq!(after_words("subset ", 1), 7);
+        assert_eq!(after_words("", 2), 0);
+    }
+}
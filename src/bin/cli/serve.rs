@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::io::Write;
+use serde::Serialize;
+use serde_json::{json, Value};
+use so_survey_analyzer::{Correction, IntervalMethod, SearchMode, SurveyAnalyzer, SurveyError, Weights};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::parse_subset_spec;
+
+/// Default number of ranked search hits returned
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Endpoints listed by `GET /`
+const ENDPOINTS: [&str; 6] = [
+    "GET /structure",
+    "GET /search?q=TERM[&mode=substring|regex|fuzzy][&target=questions|options][&limit=N]",
+    "GET /distribution?question=Q[&ci=0.95][&ci_method=wilson|clopper-pearson]",
+    "GET /subset?question=Q&option=OPTION",
+    "GET /crosstab?row=Q&column=Q",
+    "GET /compare?question=Q&left=Q:OPTION&right=Q:OPTION[&correction=holm|bh|none][&alpha=0.05]",
+];
+
+/// An HTTP error response with a JSON body
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self { status: 400, message: message.into() }
+    }
+}
+
+impl From<SurveyError> for ApiError {
+    fn from(error: SurveyError) -> Self {
+        let status = match &error {
+            SurveyError::QuestionNotFound(_)
+            | SurveyError::QuestionNameNotFound { .. }
+            | SurveyError::GroupNotFound(_)
+            | SurveyError::OptionNotFound(_) => 404,
+            SurveyError::InvalidParameter(_) | SurveyError::InvalidQuestionType => 400,
+            SurveyError::EmptyDataset => 422,
+            _ => 500,
+        };
+        Self { status, message: error.to_string() }
+    }
+}
+
+/// Decoded query string parameters
+struct Query(HashMap<String, String>);
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        Self(
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (percent_decode(key), percent_decode(value))
+                })
+                .collect(),
+        )
+    }
+
+    fn required(&self, key: &str) -> Result<&str, ApiError> {
+        self.0
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.trim().is_empty())
+            .ok_or_else(|| ApiError::bad_request(format!("Missing query parameter '{}'", key)))
+    }
+
+    /// Optional parameter parsed with `FromStr`
+    fn parsed<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, ApiError>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.0
+            .get(key)
+            .map(|value| value.parse().map_err(|e| ApiError::bad_request(format!("Invalid '{}': {}", key, e))))
+            .transpose()
+    }
+}
+
+/// Decode `%XX` escapes and `+` as space; malformed escapes are kept as-is
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| ApiError { status: 500, message: e.to_string() })
+}
+
+/// Answer one GET request
+fn route(analyzer: &SurveyAnalyzer, weights: Option<&Weights>, path: &str, query: &Query) -> Result<Value, ApiError> {
+    match path {
+        "/" => Ok(json!({ "endpoints": ENDPOINTS })),
+        "/structure" => to_json(&analyzer.get_survey_structure()),
+        "/search" => {
+            let term = query.required("q")?;
+            let mode = query.parsed::<SearchMode>("mode")?;
+            let options = match query.0.get("target").map(String::as_str) {
+                None | Some("questions") => false,
+                Some("options") => true,
+                Some(other) => {
+                    return Err(ApiError::bad_request(format!(
+                        "Invalid 'target': expected questions or options, got '{}'",
+                        other
+                    )))
+                }
+            };
+            let limit = query.parsed::<usize>("limit")?.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+            // Without a mode, search the full-text index; a mode matches every text with it
+            match (mode, options) {
+                (None, false) => to_json(&analyzer.search_questions_ranked(term, limit)),
+                (None, true) => to_json(&analyzer.search_options_ranked(term, limit)),
+                (Some(mode), false) => {
+                    to_json(&analyzer.search_questions_with(term, mode)?.into_iter().take(limit).collect::<Vec<_>>())
+                }
+                (Some(mode), true) => {
+                    to_json(&analyzer.search_options_with(term, mode)?.into_iter().take(limit).collect::<Vec<_>>())
+                }
+            }
+        }
+        "/distribution" => {
+            let question = query.required("question")?;
+            match (weights, query.parsed::<f64>("ci")?) {
+                (Some(_), Some(_)) => Err(ApiError::bad_request("Confidence intervals are not available for weighted data")),
+                (Some(weights), None) => to_json(&analyzer.get_weighted_distribution(question, weights)?),
+                (None, Some(level)) => {
+                    let method = query.parsed::<IntervalMethod>("ci_method")?.unwrap_or(IntervalMethod::Wilson);
+                    to_json(&analyzer.get_distribution_with_intervals(question, level, method)?)
+                }
+                (None, None) => to_json(&analyzer.get_distribution(question)?),
+            }
+        }
+        "/subset" => to_json(&analyzer.create_subset(query.required("question")?, query.required("option")?)?),
+        "/crosstab" => to_json(&analyzer.get_cross_tab(query.required("row")?, query.required("column")?, weights)?),
+        "/compare" => {
+            let subset = |key: &str| -> Result<_, ApiError> {
+                let (question, option) = parse_subset_spec(query.required(key)?).map_err(ApiError::bad_request)?;
+                Ok(analyzer.create_subset(question, &option)?)
+            };
+            let (left, right) = (subset("left")?, subset("right")?);
+            let correction = query.parsed::<Correction>("correction")?.unwrap_or(Correction::Holm);
+            let alpha = query.parsed::<f64>("alpha")?.unwrap_or(0.05);
+            to_json(&analyzer.compare_with(query.required("question")?, &left, &right, correction, alpha)?)
+        }
+        _ => Err(ApiError { status: 404, message: format!("Unknown endpoint: {}", path) }),
+    }
+}
+
+fn respond(request: Request, status: u16, body: &Value) {
+    let content_type = Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let response = Response::from_string(format!("{:#}\n", body))
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        let _ = writeln!(std::io::stderr(), "Could not send response: {}", e);
+    }
+}
+
+/// Serve the JSON API until the process is stopped; the survey stays loaded in memory
+pub fn serve(analyzer: &SurveyAnalyzer, weights: Option<&Weights>, address: &str) -> Result<(), SurveyError> {
+    let server = Server::http(address)
+        .map_err(|e| SurveyError::Io(std::io::Error::other(format!("Could not listen on {}: {}", address, e))))?;
+    let local = server
+        .server_addr()
+        .to_ip()
+        .map_or_else(|| address.to_string(), |addr| addr.to_string());
+    eprintln!("Listening on http://{}", local);
+
+    for request in server.incoming_requests() {
+        let url = request.url().to_string();
+        let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+        let result = if *request.method() == Method::Get {
+            route(analyzer, weights, path, &Query::parse(query))
+        } else {
+            Err(ApiError { status: 405, message: format!("Method {} not allowed; use GET", request.method()) })
+        };
+
+        match result {
+            Ok(body) => respond(request, 200, &body),
+            Err(error) => {
+                // Logging must not take the server down if stderr has gone away
+                let _ = writeln!(std::io::stderr(), "{} {} -> {} {}", request.method(), url, error.status, error.message);
+                respond(request, error.status, &json!({ "error": error.message, "status": error.status }));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("United+States%20of%20America"), "United States of America");
+        assert_eq!(percent_decode("C%2B%2B"), "C++");
+        assert_eq!(percent_decode("Caf%C3%A9"), "Café");
+        // Malformed or truncated escapes are kept as-is
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn test_query_parameters() {
+        let query = Query::parse("question=RemoteWork&option=Hybrid+%28some+remote%29&ci=0.9&limit=x&empty=");
+        assert_eq!(query.required("option").unwrap(), "Hybrid (some remote)");
+        assert_eq!(query.parsed::<f64>("ci").unwrap(), Some(0.9));
+        assert_eq!(query.parsed::<f64>("alpha").unwrap(), None);
+        assert_eq!(query.parsed::<usize>("limit").unwrap_err().status, 400);
+        assert_eq!(query.required("empty").unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_error_status() {
+        let status = |error: SurveyError| ApiError::from(error).status;
+        assert_eq!(status(SurveyError::QuestionNotFound(7)), 404);
+        assert_eq!(status(SurveyError::OptionNotFound("Rust".to_string())), 404);
+        assert_eq!(status(SurveyError::GroupNotFound("Language".to_string())), 404);
+        assert_eq!(status(SurveyError::InvalidParameter("alpha".to_string())), 400);
+        assert_eq!(status(SurveyError::EmptyDataset), 422);
+        assert_eq!(status(SurveyError::DataParsingError("bad row".to_string())), 500);
+
+        let error = ApiError::from(SurveyError::InvalidParameter("Level must be between 0 and 1".to_string()));
+        assert!(error.message.contains("Level must be between 0 and 1"));
+    }
+}
@@ -0,0 +1,649 @@
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufWriter, Write};
+use std::path::PathBuf;
+use so_survey_analyzer::{
+    BarChart, DataFile, LoadOptions, QuestionRef, SavedSubset, SearchMode, SearchTarget, Subset, SurveyAnalyzer,
+    SurveyError, Weights, Workspace,
+};
+
+use super::{parse_search_args, print_search_matches, terminal_width};
+
+/// Maximum number of regex/fuzzy matches listed by a session
+const SEARCH_LIMIT: usize = 20;
+
+/// Number of options drawn as bars before summing the rest into "Other"
+const BAR_LIMIT: usize = 15;
+
+/// Session commands, as offered for completion
+pub const COMMANDS: [&str; 14] = [
+    "list", "search", "searchopt", "dist", "subset", "let", "subsets", "drop", "set", "vars", "save", "help", "quit",
+    "exit",
+];
+
+/// Commands that change session state, re-run when a workspace is reopened
+const STATE_COMMANDS: [&str; 3] = ["let", "drop", "set"];
+
+/// Commands left out of the command log
+const UNLOGGED_COMMANDS: [&str; 4] = ["save", "help", "quit", "exit"];
+
+/// Set operators usable between saved subsets
+const SUBSET_OPERATORS: [char; 3] = ['&', '|', '-'];
+
+/// What the caller should do after a command
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Where a session's data came from, recorded by `save`
+pub enum Inputs {
+    /// A reopened workspace, whose data files were verified when it was opened
+    Opened(Workspace),
+    /// Files named on the command line, hashed only when the session is saved
+    Files { data: PathBuf, weight_column: Option<QuestionRef>, rake: Option<PathBuf> },
+}
+
+/// A subset saved with `let`, with the text that defined it
+struct NamedSubset {
+    subset: Subset,
+    definition: String,
+}
+
+/// Command interpreter shared by the REPL and batch scripts, with the state carried between commands
+pub struct Session<'a> {
+    analyzer: &'a SurveyAnalyzer,
+    weights: Option<&'a Weights>,
+    subsets: BTreeMap<String, NamedSubset>,
+    variables: BTreeMap<String, String>,
+    log: Vec<String>, // successful commands, variables expanded
+    inputs: Option<Inputs>, // data files and load options recorded by `save`
+    color: bool, // highlight search matches with ANSI colors
+}
+
+impl<'a> Session<'a> {
+    pub fn new(analyzer: &'a SurveyAnalyzer, weights: Option<&'a Weights>, color: bool) -> Self {
+        Self {
+            analyzer,
+            weights,
+            subsets: BTreeMap::new(),
+            variables: BTreeMap::new(),
+            log: Vec::new(),
+            inputs: None,
+            color,
+        }
+    }
+
+    /// Record the data files and load options so `save` can write a workspace
+    pub fn with_inputs(mut self, inputs: Inputs) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    /// Rebuild the saved subsets and variables of a reopened workspace by re-running its state
+    /// commands; returns the number of subsets restored, or `None` if no workspace was reopened
+    pub fn restore(&mut self) -> Result<Option<usize>, SurveyError> {
+        let Some(Inputs::Opened(workspace)) = &self.inputs else {
+            return Ok(None);
+        };
+        let commands = workspace.commands.clone();
+        for command in &commands {
+            let name = command.split_whitespace().next().unwrap_or_default();
+            if STATE_COMMANDS.contains(&name) {
+                self.run_command(command, &mut std::io::sink(), false)?;
+            }
+        }
+        self.log = commands;
+        Ok(Some(self.subsets.len()))
+    }
+
+    /// Workspace to save: the reopened one, or a new one pinning the command-line files
+    fn base_workspace(&self) -> Result<Workspace, SurveyError> {
+        match &self.inputs {
+            Some(Inputs::Opened(workspace)) => Ok(workspace.clone()),
+            Some(Inputs::Files { data, weight_column, rake }) => {
+                let rake = rake.as_ref().map(DataFile::hash).transpose()?;
+                Ok(Workspace::new(DataFile::hash(data)?, LoadOptions { weight_column: weight_column.clone(), rake }))
+            }
+            None => Err(SurveyError::InvalidParameter("This session has no data file to record".to_string())),
+        }
+    }
+
+    /// Define a variable that `$name` / `${name}` expand to
+    pub fn set_variable(&mut self, name: &str, value: &str) -> Result<(), SurveyError> {
+        if !is_identifier(name) {
+            return Err(SurveyError::InvalidParameter(format!(
+                "Invalid variable name '{}': use letters, digits and underscores, not starting with a digit",
+                name
+            )));
+        }
+        self.variables.insert(name.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn subset_names(&self) -> Vec<String> {
+        self.subsets.keys().cloned().collect()
+    }
+
+    /// Run one command line, writing to `out` unless the line ends in `> file` or `>> file`
+    pub fn execute(&mut self, line: &str, out: &mut dyn Write) -> Result<Flow, SurveyError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Flow::Continue);
+        }
+        let line = self.substitute(line)?;
+
+        let (command, redirect) = split_redirect(&line)?;
+        let flow = match redirect {
+            None => self.run_command(command, out, self.color)?,
+            Some(redirect) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(redirect.append)
+                    .truncate(!redirect.append)
+                    .open(redirect.path)?;
+                let mut writer = BufWriter::new(file);
+                let flow = self.run_command(command, &mut writer, false)?;
+                writer.flush()?;
+                flow
+            }
+        };
+
+        if !UNLOGGED_COMMANDS.contains(&command.split_whitespace().next().unwrap_or_default()) {
+            self.log.push(line);
+        }
+        Ok(flow)
+    }
+
+    fn run_command(&mut self, line: &str, out: &mut dyn Write, color: bool) -> Result<Flow, SurveyError> {
+        let usage = |text: &str| SurveyError::InvalidParameter(format!("Usage: {}", text));
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(command) = parts.first() else {
+            return Ok(Flow::Continue);
+        };
+        let analyzer = self.analyzer;
+
+        match *command {
+            "quit" | "exit" => return Ok(Flow::Quit),
+            "help" => write_help(out)?,
+            "list" => {
+                let limit = parts.get(1).and_then(|s| s.parse().ok());
+                let questions = analyzer.get_survey_structure();
+                let count = limit.unwrap_or(questions.len()).min(questions.len());
+
+                for question in questions.iter().take(count) {
+                    writeln!(out, "{}: {}", question.id, question.text)?;
+                }
+                writeln!(out, "({} of {} questions shown)", count, questions.len())?;
+            }
+            "search" => {
+                let (mode, term) = parse_search_args(&parts[1..]);
+                if term.is_empty() {
+                    return Err(usage("search [--regex|--fuzzy] <term>"));
+                }
+                if mode != SearchMode::Substring {
+                    let results = analyzer.search_questions_with(&term, mode)?;
+                    print_search_matches(out, &results, SEARCH_LIMIT, color)?;
+                    return Ok(Flow::Continue);
+                }
+                for hit in analyzer.search_questions_ranked(&term, SEARCH_LIMIT) {
+                    if let SearchTarget::Question { question_id } = hit.target {
+                        writeln!(out, "{}: {}", question_id, hit.text)?;
+                    }
+                }
+            }
+            "searchopt" => {
+                let (mode, term) = parse_search_args(&parts[1..]);
+                if term.is_empty() {
+                    return Err(usage("searchopt [--regex|--fuzzy] <term>"));
+                }
+                if mode != SearchMode::Substring {
+                    let results = analyzer.search_options_with(&term, mode)?;
+                    print_search_matches(out, &results, SEARCH_LIMIT, color)?;
+                    return Ok(Flow::Continue);
+                }
+                for hit in analyzer.search_options_ranked(&term, SEARCH_LIMIT) {
+                    if let SearchTarget::Option { question_id, option } = hit.target {
+                        writeln!(out, "Q{}: {}", question_id, option)?;
+                    }
+                }
+            }
+            "dist" => {
+                let (bars, args) = match parts.get(1) {
+                    Some(&"--bars") => (true, &parts[2..]),
+                    _ => (false, &parts[1..]),
+                };
+                let Some(question) = args.first() else {
+                    return Err(usage("dist [--bars] <question> [in <subsets>]"));
+                };
+                let distribution = match (args.get(1), self.weights) {
+                    (Some(&"in"), Some(_)) => {
+                        return Err(SurveyError::InvalidParameter(
+                            "Subset distributions are not available for weighted data".to_string(),
+                        ))
+                    }
+                    (Some(&"in"), None) => {
+                        analyzer.get_subset_distribution(*question, &self.evaluate(&args[2..].join(" "))?)?
+                    }
+                    (Some(_), _) => return Err(usage("dist [--bars] <question> [in <subsets>]")),
+                    (None, Some(weights)) => {
+                        let distribution = analyzer.get_weighted_distribution(*question, weights)?;
+                        if bars {
+                            let chart = BarChart::from_weighted_distribution(&distribution).top_with_other(BAR_LIMIT);
+                            writeln!(out, "Total weight: {:.1} ({} responses)\n", distribution.total_weight, distribution.total_responses)?;
+                            writeln!(out, "{}", chart.to_text(terminal_width()))?;
+                        } else {
+                            writeln!(out, "{}", distribution.display())?;
+                        }
+                        return Ok(Flow::Continue);
+                    }
+                    (None, None) => analyzer.get_distribution(*question)?,
+                };
+                if bars {
+                    let chart = BarChart::from_distribution(&distribution).top_with_other(BAR_LIMIT);
+                    writeln!(out, "Total responses: {}\n", distribution.total_responses)?;
+                    writeln!(out, "{}", chart.to_text(terminal_width()))?;
+                } else {
+                    writeln!(out, "{}", distribution.display())?;
+                }
+            }
+            "subset" => {
+                if parts.len() < 3 {
+                    return Err(usage("subset <question> <option>"));
+                }
+                let subset = analyzer.create_subset(parts[1], &parts[2..].join(" "))?;
+                writeln!(out, "{}", subset.display())?;
+            }
+            "let" => {
+                let (name, definition) = line[3..]
+                    .split_once('=')
+                    .ok_or_else(|| usage("let <name> = subset <question> <option> | let <name> = <a> & <b>"))?;
+                let (name, definition) = (name.trim(), definition.trim());
+                let named = self.define(name, definition)?;
+                writeln!(
+                    out,
+                    "{} = {}: {} respondents ({:.1}% of total)",
+                    name,
+                    named.definition,
+                    named.subset.size(),
+                    named.subset.percentage()
+                )?;
+            }
+            "subsets" => {
+                if self.subsets.is_empty() {
+                    writeln!(out, "No saved subsets. Save one with: let <name> = subset <question> <option>")?;
+                }
+                for (name, named) in &self.subsets {
+                    writeln!(
+                        out,
+                        "  {} = {}: {} respondents ({:.1}%)",
+                        name,
+                        named.definition,
+                        named.subset.size(),
+                        named.subset.percentage()
+                    )?;
+                }
+            }
+            "drop" => {
+                let name = parts.get(1).ok_or_else(|| usage("drop <name>"))?;
+                if self.subsets.remove(*name).is_none() {
+                    return Err(SurveyError::InvalidParameter(format!("Unknown subset '{}'", name)));
+                }
+                writeln!(out, "Dropped subset '{}'", name)?;
+            }
+            "set" => {
+                let (name, value) = line[3..].split_once('=').ok_or_else(|| usage("set <name> = <value>"))?;
+                self.set_variable(name.trim(), value.trim())?;
+            }
+            "vars" => {
+                for (name, value) in &self.variables {
+                    writeln!(out, "  {} = {}", name, value)?;
+                }
+            }
+            "save" => {
+                let path = parts.get(1).ok_or_else(|| usage("save <workspace.json>"))?;
+                let mut workspace = self.base_workspace()?;
+                workspace.subsets = self
+                    .subsets
+                    .iter()
+                    .map(|(name, named)| SavedSubset { name: name.clone(), definition: named.definition.clone() })
+                    .collect();
+                workspace.variables = self.variables.clone();
+                workspace.commands = self.log.clone();
+                workspace.save(path)?;
+                writeln!(out, "Saved workspace with {} command(s) to {}", workspace.commands.len(), path)?;
+            }
+            _ => {
+                return Err(SurveyError::InvalidParameter(format!(
+                    "Unknown command: {}. Type 'help' for available commands.",
+                    command
+                )))
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Save `subset <question> <option>` or a set expression over saved subsets under `name`
+    fn define(&mut self, name: &str, definition: &str) -> Result<&NamedSubset, SurveyError> {
+        if !is_identifier(name) {
+            return Err(SurveyError::InvalidParameter(format!(
+                "Invalid subset name '{}': use letters, digits and underscores, not starting with a digit",
+                name
+            )));
+        }
+
+        let subset = match definition.strip_prefix("subset ") {
+            Some(spec) => {
+                let mut words = spec.split_whitespace();
+                let question = words.next().unwrap_or_default();
+                let option = words.collect::<Vec<_>>().join(" ");
+                if option.is_empty() {
+                    return Err(SurveyError::InvalidParameter("Expected: subset <question> <option>".to_string()));
+                }
+                self.analyzer.create_subset(question, &option)?
+            }
+            None => self.evaluate(definition)?,
+        };
+
+        let named = NamedSubset { subset, definition: definition.to_string() };
+        Ok(self.subsets.entry(name.to_string()).insert_entry(named).into_mut())
+    }
+
+    /// Evaluate saved subsets combined with `&` (and), `|` (or) and `-` (and not), left to right
+    fn evaluate(&self, expression: &str) -> Result<Subset, SurveyError> {
+        let invalid = |message: String| SurveyError::InvalidParameter(message);
+        let lookup = |name: &str| {
+            self.subsets.get(name).map(|named| named.subset.clone()).ok_or_else(|| {
+                invalid(format!("Unknown subset '{}' (saved subsets: {})", name, self.subset_names().join(", ")))
+            })
+        };
+
+        let mut tokens = Vec::new();
+        let mut rest = expression.trim();
+        while !rest.is_empty() {
+            if let Some(operator) = rest.chars().next().filter(|c| SUBSET_OPERATORS.contains(c)) {
+                tokens.push(operator.to_string());
+                rest = rest[1..].trim_start();
+                continue;
+            }
+            let end = rest.find(|c: char| c.is_whitespace() || SUBSET_OPERATORS.contains(&c)).unwrap_or(rest.len());
+            tokens.push(rest[..end].to_string());
+            rest = rest[end..].trim_start();
+        }
+
+        let mut tokens = tokens.iter();
+        let first = tokens.next().ok_or_else(|| invalid("Expected a subset name".to_string()))?;
+        let mut result = lookup(first)?;
+        while let Some(operator) = tokens.next() {
+            let name = tokens
+                .next()
+                .ok_or_else(|| invalid(format!("Expected a subset name after '{}'", operator)))?;
+            let other = lookup(name)?;
+            result = match operator.as_str() {
+                "&" => result.intersection(&other),
+                "|" => result.union(&other),
+                "-" => result.difference(&other),
+                _ => return Err(invalid(format!("Expected &, | or - between subsets, got '{}'", operator))),
+            };
+        }
+        Ok(result)
+    }
+
+    /// Expand `$name` and `${name}`; `$$` is a literal `$`, as is a `$` not followed by a name
+    /// (so regex anchors like `Rust$` pass through)
+    fn substitute(&self, line: &str) -> Result<String, SurveyError> {
+        let mut output = String::with_capacity(line.len());
+        let mut rest = line;
+        while let Some(i) = rest.find('$') {
+            output.push_str(&rest[..i]);
+            rest = &rest[i + 1..];
+
+            let (name, remainder) = if rest.starts_with('$') {
+                output.push('$');
+                rest = &rest[1..];
+                continue;
+            } else if let Some(braced) = rest.strip_prefix('{') {
+                let end = braced
+                    .find('}')
+                    .ok_or_else(|| SurveyError::InvalidParameter("Unclosed ${ in command".to_string()))?;
+                (&braced[..end], &braced[end + 1..])
+            } else {
+                let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                if !is_identifier(&rest[..end]) {
+                    output.push('$');
+                    continue;
+                }
+                (&rest[..end], &rest[end..])
+            };
+
+            let value = self
+                .variables
+                .get(name)
+                .ok_or_else(|| SurveyError::InvalidParameter(format!("Unknown variable '${}'", name)))?;
+            output.push_str(value);
+            rest = remainder;
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+}
+
+/// Output file named by a trailing `> path` (truncate) or `>> path` (append)
+struct Redirect<'a> {
+    path: &'a str,
+    append: bool,
+}
+
+/// Split a command line into the command and its redirection, if any. Only a standalone `>` or
+/// `>>` word before the last word redirects; a `>` inside an argument (`a>b`) is left alone.
+fn split_redirect(line: &str) -> Result<(&str, Option<Redirect<'_>>), SurveyError> {
+    let missing_file = || SurveyError::InvalidParameter("Missing file name after '>'".to_string());
+    let line = line.trim_end();
+    let path_start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let path = &line[path_start..];
+    if matches!(path, ">" | ">>") {
+        return Err(missing_file());
+    }
+
+    let before = line[..path_start].trim_end();
+    let operator_start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let append = match &before[operator_start..] {
+        ">" => false,
+        ">>" => true,
+        _ => return Ok((line, None)),
+    };
+    Ok((before[..operator_start].trim_end(), Some(Redirect { path, append })))
+}
+
+/// Subset and variable names are identifiers so they never clash with operators
+fn is_identifier(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+pub fn write_help(out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "Available commands:")?;
+    writeln!(out, "  list [limit] - List questions (optionally limit to N questions)")?;
+    writeln!(out, "  search [--regex|--fuzzy] <term> - Search questions")?;
+    writeln!(out, "  searchopt [--regex|--fuzzy] <term> - Search options")?;
+    writeln!(out, "  dist [--bars] <question> [in <subsets>] - Show distribution (ID or column name), optionally as bars")?;
+    writeln!(out, "  subset <question> <option> - Create subset")?;
+    writeln!(out, "  let <name> = subset <question> <option> - Save a subset")?;
+    writeln!(out, "  let <name> = <a> & <b> | <c> - <d> - Save a combination of saved subsets (left to right)")?;
+    writeln!(out, "  subsets - List saved subsets")?;
+    writeln!(out, "  drop <name> - Delete a saved subset")?;
+    writeln!(out, "  set <name> = <value> - Define a variable used as $name or ${{name}}")?;
+    writeln!(out, "  vars - List variables")?;
+    writeln!(out, "  save <workspace.json> - Save the data file hashes, subsets and command log")?;
+    writeln!(out, "  help - Show this help")?;
+    writeln!(out, "  quit - Exit")?;
+    writeln!(out, "Append '> file' or '>> file' to a command to write its output to a file.")
+}
+
+/// Run commands from a script, one per line; errors are reported with their line number
+pub fn run_script(session: &mut Session, reader: impl BufRead, source: &str, fail_fast: bool) -> Result<(), SurveyError> {
+    run_lines(session, reader.lines(), source, fail_fast)
+}
+
+/// Re-run the command log of the session's reopened workspace, regenerating its output and redirected files
+pub fn replay(session: &mut Session, source: &str, fail_fast: bool) -> Result<(), SurveyError> {
+    let Some(Inputs::Opened(workspace)) = &session.inputs else {
+        return Err(SurveyError::InvalidParameter("Only a reopened workspace can be replayed".to_string()));
+    };
+    let commands = workspace.commands.clone();
+    run_lines(session, commands.into_iter().map(Ok), source, fail_fast)
+}
+
+fn run_lines(
+    session: &mut Session,
+    lines: impl Iterator<Item = std::io::Result<String>>,
+    source: &str,
+    fail_fast: bool,
+) -> Result<(), SurveyError> {
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let mut failed = 0;
+
+    for (number, line) in lines.enumerate() {
+        let line = line?;
+        match session.execute(&line, &mut out) {
+            Ok(Flow::Quit) => break,
+            Ok(Flow::Continue) => {}
+            Err(e) => {
+                out.flush()?;
+                eprintln!("{}:{}: {}", source, number + 1, e);
+                failed += 1;
+                if fail_fast {
+                    return Err(SurveyError::InvalidParameter(format!(
+                        "Stopped at {}:{} (--fail-fast)",
+                        source,
+                        number + 1
+                    )));
+                }
+            }
+        }
+    }
+
+    out.flush()?;
+    if failed > 0 {
+        return Err(SurveyError::InvalidParameter(format!("{} command(s) in {} failed", failed, source)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyzer() -> SurveyAnalyzer {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_row(0, 0, ["ResponseId", "MainBranch", "RemoteWork"]).unwrap();
+        sheet.write_row(1, 0, ["1", "Developer", "Remote"]).unwrap();
+        sheet.write_row(2, 0, ["2", "Hobbyist", "Hybrid"]).unwrap();
+        sheet.write_row(3, 0, ["3", "Developer", "Hybrid"]).unwrap();
+        sheet.write_row(4, 0, ["4", "Developer", "Remote"]).unwrap();
+        SurveyAnalyzer::from_excel_bytes(&workbook.save_to_buffer().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_substitute() {
+        let analyzer = analyzer();
+        let mut session = Session::new(&analyzer, None, false);
+        session.set_variable("q", "RemoteWork").unwrap();
+
+        assert_eq!(session.substitute("dist $q").unwrap(), "dist RemoteWork");
+        assert_eq!(session.substitute("dist ${q}_2").unwrap(), "dist RemoteWork_2");
+        assert_eq!(session.substitute("search costs $$5").unwrap(), "search costs $5");
+        // A `$` not followed by a name is kept, so regex anchors work
+        assert_eq!(session.substitute("search --regex Rust$").unwrap(), "search --regex Rust$");
+        assert_eq!(session.substitute("search --regex ^(Go|Rust)$ $1").unwrap(), "search --regex ^(Go|Rust)$ $1");
+
+        assert!(session.substitute("dist $missing").is_err());
+        assert!(session.substitute("dist ${q").is_err());
+    }
+
+    #[test]
+    fn test_split_redirect() {
+        let (command, redirect) = split_redirect("dist RemoteWork > out.txt").unwrap();
+        let redirect = redirect.unwrap();
+        assert_eq!((command, redirect.path, redirect.append), ("dist RemoteWork", "out.txt", false));
+
+        let (command, redirect) = split_redirect("dist RemoteWork >> out.txt").unwrap();
+        let redirect = redirect.unwrap();
+        assert_eq!((command, redirect.path, redirect.append), ("dist RemoteWork", "out.txt", true));
+
+        // `>` inside an argument is not a redirect
+        for line in ["search --regex a>b", "search --regex a >b", "subset Salary >100"] {
+            let (command, redirect) = split_redirect(line).unwrap();
+            assert_eq!(command, line);
+            assert!(redirect.is_none());
+        }
+
+        assert!(split_redirect("dist RemoteWork >").is_err());
+        assert!(split_redirect("dist RemoteWork >>").is_err());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let analyzer = analyzer();
+        let mut session = Session::new(&analyzer, None, false);
+        let mut out = Vec::new();
+        session.execute("let dev = subset MainBranch Developer", &mut out).unwrap();
+        session.execute("let remote = subset RemoteWork Remote", &mut out).unwrap();
+        session.execute("let hybrid = subset RemoteWork Hybrid", &mut out).unwrap();
+
+        assert_eq!(session.evaluate("dev & remote").unwrap().respondent_ids, vec![0, 3]);
+        assert_eq!(session.evaluate("dev-remote").unwrap().respondent_ids, vec![2]);
+        // Operators apply left to right
+        assert_eq!(session.evaluate("remote | hybrid & dev").unwrap().respondent_ids, vec![0, 2, 3]);
+
+        let error = session.evaluate("dev & backend").unwrap_err().to_string();
+        assert!(error.contains("Unknown subset 'backend'"), "{}", error);
+        assert!(error.contains("dev, hybrid, remote"), "{}", error);
+        assert!(session.evaluate("dev &").is_err());
+        assert!(session.evaluate("").is_err());
+    }
+
+    #[test]
+    fn test_weighted_dist_rejects_subsets() {
+        let analyzer = analyzer();
+        let weights = Weights::from_values(vec![1.0, 2.0, 1.0, 2.0]).unwrap();
+        let mut session = Session::new(&analyzer, Some(&weights), false);
+        let mut out = Vec::new();
+        session.execute("let dev = subset MainBranch Developer", &mut out).unwrap();
+
+        session.execute("dist RemoteWork", &mut out).unwrap();
+        let result = session.execute("dist RemoteWork in dev", &mut out);
+        assert!(matches!(result, Err(SurveyError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_save_hashes_data_files_when_saving() {
+        let analyzer = analyzer();
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("survey.csv");
+        let inputs = Inputs::Files { data: data.clone(), weight_column: None, rake: None };
+        // Nothing is read until `save`, so a missing file only fails there
+        let mut session = Session::new(&analyzer, None, false).with_inputs(inputs);
+        assert_eq!(session.restore().unwrap(), None);
+        session.execute("let dev = subset MainBranch Developer", &mut std::io::sink()).unwrap();
+
+        let path = dir.path().join("session.json");
+        let save = format!("save {}", path.display());
+        assert!(session.execute(&save, &mut std::io::sink()).is_err());
+        std::fs::write(&data, "abc").unwrap();
+        session.execute(&save, &mut std::io::sink()).unwrap();
+
+        let workspace = Workspace::open(&path).unwrap();
+        assert_eq!(workspace.data.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(workspace.commands, vec!["let dev = subset MainBranch Developer"]);
+        assert!(replay(&mut session, "<test>", true).is_err());
+
+        // A reopened workspace restores its subsets and keeps its recorded data file
+        let mut reopened = Session::new(&analyzer, None, false).with_inputs(Inputs::Opened(workspace.clone()));
+        assert_eq!(reopened.restore().unwrap(), Some(1));
+        assert_eq!(reopened.base_workspace().unwrap().data, workspace.data);
+    }
+}
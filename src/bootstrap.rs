@@ -0,0 +1,363 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::analysis::Subset;
+use crate::error::{SurveyError, Result};
+use crate::stats;
+
+/// How a bootstrap confidence interval is read off the resampled statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BootstrapMethod {
+    Percentile,
+    Bca, // bias-corrected and accelerated; costs up to 100 extra statistic evaluations (grouped jackknife)
+}
+
+impl FromStr for BootstrapMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "percentile" => Ok(BootstrapMethod::Percentile),
+            "bca" => Ok(BootstrapMethod::Bca),
+            _ => Err(format!("Unknown bootstrap method: {} (expected percentile or bca)", s)),
+        }
+    }
+}
+
+impl fmt::Display for BootstrapMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BootstrapMethod::Percentile => write!(f, "percentile"),
+            BootstrapMethod::Bca => write!(f, "BCa"),
+        }
+    }
+}
+
+/// Groups left out in turn by the BCa jackknife, bounding its cost on large subsets
+const JACKKNIFE_GROUPS: usize = 100;
+
+/// Common statistics of numeric answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumericStatistic {
+    Mean,
+    Median,
+}
+
+impl NumericStatistic {
+    /// Compute the statistic over a list of values (NaN if empty)
+    pub fn compute(&self, values: &[f64]) -> f64 {
+        if values.is_empty() {
+            return f64::NAN;
+        }
+
+        match self {
+            NumericStatistic::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            NumericStatistic::Median => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(f64::total_cmp);
+                let mid = sorted.len() / 2;
+                if sorted.len().is_multiple_of(2) {
+                    (sorted[mid - 1] + sorted[mid]) / 2.0
+                } else {
+                    sorted[mid]
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for NumericStatistic {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mean" => Ok(NumericStatistic::Mean),
+            "median" => Ok(NumericStatistic::Median),
+            _ => Err(format!("Unknown statistic: {} (expected mean or median)", s)),
+        }
+    }
+}
+
+/// Seeded bootstrap resampling over respondent IDs.
+/// Resample `i` always draws from the same random stream, so results do not depend on `threads`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bootstrap {
+    pub resamples: usize,
+    pub seed: u64,
+    pub threads: usize,
+    pub level: f64,
+    pub method: BootstrapMethod,
+}
+
+impl Default for Bootstrap {
+    fn default() -> Self {
+        Self {
+            resamples: 1000,
+            seed: 0,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            level: 0.95,
+            method: BootstrapMethod::Percentile,
+        }
+    }
+}
+
+/// Point estimate and bootstrap confidence interval of a statistic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapResult {
+    pub estimate: f64,
+    pub std_error: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub level: f64,
+    pub method: BootstrapMethod,
+    pub resamples: usize, // replicates with a finite statistic
+    pub sample_size: usize,
+}
+
+impl BootstrapResult {
+    /// Display the estimate and interval
+    pub fn display(&self) -> String {
+        format!(
+            "Estimate: {:.2} (std error {:.2})\n\
+            {:.0}% {} interval: {:.2} to {:.2}\n\
+            Sample size: {}, resamples: {}",
+            self.estimate,
+            self.std_error,
+            self.level * 100.0,
+            self.method,
+            self.lower,
+            self.upper,
+            self.sample_size,
+            self.resamples
+        )
+    }
+}
+
+impl Bootstrap {
+    /// Bootstrap a statistic over the respondents of a subset
+    pub fn run<F>(&self, subset: &Subset, statistic: F) -> Result<BootstrapResult>
+    where
+        F: Fn(&[usize]) -> f64 + Sync,
+    {
+        self.run_on(&subset.respondent_ids, statistic)
+    }
+
+    /// Bootstrap a statistic over an explicit list of respondent IDs
+    pub fn run_on<F>(&self, respondent_ids: &[usize], statistic: F) -> Result<BootstrapResult>
+    where
+        F: Fn(&[usize]) -> f64 + Sync,
+    {
+        if respondent_ids.is_empty() {
+            return Err(SurveyError::EmptyDataset);
+        }
+        if self.resamples == 0 {
+            return Err(SurveyError::InvalidParameter("At least one resample is required".to_string()));
+        }
+        if !(self.level > 0.0 && self.level < 1.0) {
+            return Err(SurveyError::InvalidParameter(format!(
+                "Confidence level must be between 0 and 1, got {}",
+                self.level
+            )));
+        }
+
+        let n = respondent_ids.len();
+        let estimate = statistic(respondent_ids);
+
+        let mut replicates: Vec<f64> = self
+            .parallel_map(self.resamples, |i| {
+                let mut rng = SplitMix64::for_stream(self.seed, i as u64);
+                let sample: Vec<usize> = (0..n).map(|_| respondent_ids[rng.below(n)]).collect();
+                statistic(&sample)
+            })
+            .into_iter()
+            .filter(|value| value.is_finite())
+            .collect();
+        if replicates.is_empty() {
+            return Err(SurveyError::EmptyDataset);
+        }
+        replicates.sort_by(f64::total_cmp);
+
+        let count = replicates.len() as f64;
+        let mean = replicates.iter().sum::<f64>() / count;
+        let std_error = if replicates.len() > 1 {
+            (replicates.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (count - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        let alpha = 1.0 - self.level;
+        let (low_quantile, high_quantile) = match self.method {
+            BootstrapMethod::Percentile => (alpha / 2.0, 1.0 - alpha / 2.0),
+            BootstrapMethod::Bca => self.bca_quantiles(respondent_ids, &statistic, estimate, &replicates),
+        };
+
+        Ok(BootstrapResult {
+            estimate,
+            std_error,
+            lower: quantile(&replicates, low_quantile),
+            upper: quantile(&replicates, high_quantile),
+            level: self.level,
+            method: self.method,
+            resamples: replicates.len(),
+            sample_size: n,
+        })
+    }
+
+    /// Adjusted quantile levels for the BCa interval
+    fn bca_quantiles<F>(&self, respondent_ids: &[usize], statistic: &F, estimate: f64, replicates: &[f64]) -> (f64, f64)
+    where
+        F: Fn(&[usize]) -> f64 + Sync,
+    {
+        // Bias correction: how far the replicates are shifted from the point estimate
+        let below = replicates.iter().filter(|r| **r < estimate).count() as f64;
+        let proportion = (below / replicates.len() as f64).clamp(1e-10, 1.0 - 1e-10);
+        let z0 = stats::normal_quantile(proportion);
+
+        // Acceleration from a grouped jackknife: leave out each of up to JACKKNIFE_GROUPS
+        // contiguous blocks of respondents in turn (plain leave-one-out for small subsets)
+        let n = respondent_ids.len();
+        let groups = n.min(JACKKNIFE_GROUPS);
+        let jackknife: Vec<f64> = self
+            .parallel_map(groups, |g| {
+                let (start, end) = (g * n / groups, (g + 1) * n / groups);
+                let sample: Vec<usize> = [&respondent_ids[..start], &respondent_ids[end..]].concat();
+                statistic(&sample)
+            })
+            .into_iter()
+            .filter(|value| value.is_finite())
+            .collect();
+        let jackknife_mean = jackknife.iter().sum::<f64>() / jackknife.len().max(1) as f64;
+        let (cubes, squares) = jackknife.iter().fold((0.0, 0.0), |(cubes, squares), value| {
+            let d = jackknife_mean - value;
+            (cubes + d.powi(3), squares + d * d)
+        });
+        let acceleration = if squares > 0.0 { cubes / (6.0 * squares.powf(1.5)) } else { 0.0 };
+
+        let adjust = |q: f64| {
+            let z = stats::normal_quantile(q);
+            stats::normal_cdf(z0 + (z0 + z) / (1.0 - acceleration * (z0 + z)))
+        };
+        let alpha = 1.0 - self.level;
+        (adjust(alpha / 2.0), adjust(1.0 - alpha / 2.0))
+    }
+
+    /// Evaluate `f(0..count)` across worker threads, returning results in index order
+    fn parallel_map<F>(&self, count: usize, f: F) -> Vec<f64>
+    where
+        F: Fn(usize) -> f64 + Sync,
+    {
+        // wasm32 without atomics cannot spawn threads
+        let threads = if cfg!(target_arch = "wasm32") { 1 } else { self.threads.clamp(1, count.max(1)) };
+        if threads == 1 {
+            return (0..count).map(f).collect();
+        }
+
+        let chunk_size = count.div_ceil(threads);
+        let f = &f;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|t| {
+                    let range = (t * chunk_size)..((t + 1) * chunk_size).min(count);
+                    scope.spawn(move || range.map(f).collect::<Vec<f64>>())
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("bootstrap worker panicked"))
+                .collect()
+        })
+    }
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+/// Small, fast, seedable generator (SplitMix64)
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Independent stream `index` of a seed: both are hashed, so distinct `(seed, index)`
+    /// pairs don't share a starting state the way `seed ^ index * constant` can
+    fn for_stream(seed: u64, index: u64) -> Self {
+        Self { state: Self::mix(Self::mix(seed) ^ index) }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        Self::mix(self.state)
+    }
+
+    /// SplitMix64 output function, a bijective 64-bit hash
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform integer in `0..bound`
+    fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_is_deterministic_across_threads() {
+        let values: Vec<f64> = (0..200).map(|i| (i % 37) as f64 * 1.5).collect();
+        let ids: Vec<usize> = (0..values.len()).collect();
+        let mean = |sample: &[usize]| {
+            NumericStatistic::Mean.compute(&sample.iter().map(|&id| values[id]).collect::<Vec<_>>())
+        };
+
+        let single = Bootstrap { resamples: 500, seed: 42, threads: 1, ..Bootstrap::default() };
+        let parallel = Bootstrap { threads: 4, ..single.clone() };
+
+        let a = single.run_on(&ids, mean).unwrap();
+        let b = parallel.run_on(&ids, mean).unwrap();
+        assert_eq!((a.lower, a.upper, a.std_error), (b.lower, b.upper, b.std_error));
+        assert!(a.lower < a.estimate && a.estimate < a.upper);
+
+        let bca = Bootstrap { method: BootstrapMethod::Bca, ..parallel }.run_on(&ids, mean).unwrap();
+        assert!(bca.lower < bca.estimate && bca.estimate < bca.upper);
+        assert!((bca.lower - a.lower).abs() < 1.0);
+
+        // The grouped jackknife keeps BCa cheap: at most JACKKNIFE_GROUPS extra evaluations
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let counted = |sample: &[usize]| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            mean(sample)
+        };
+        let large: Vec<usize> = (0..5_000).map(|i| i % values.len()).collect();
+        let bca = Bootstrap { resamples: 50, threads: 1, method: BootstrapMethod::Bca, ..single.clone() };
+        let result = bca.run_on(&large, counted).unwrap();
+        assert_eq!(calls.into_inner(), 1 + 50 + JACKKNIFE_GROUPS);
+        assert!(result.lower < result.estimate && result.estimate < result.upper);
+    }
+
+    #[test]
+    fn test_seed_streams_differ() {
+        // `seed ^ i * constant` maps (0, 1) and (constant, 0) to the same state
+        let constant = 0x9E37_79B9_7F4A_7C15;
+        assert_ne!(SplitMix64::for_stream(0, 1).next_u64(), SplitMix64::for_stream(constant, 0).next_u64());
+        assert_ne!(SplitMix64::for_stream(7, 0).next_u64(), SplitMix64::for_stream(7, 1).next_u64());
+    }
+
+    #[test]
+    fn test_numeric_statistics() {
+        assert_eq!(NumericStatistic::Median.compute(&[3.0, 1.0, 2.0, 10.0]), 2.5);
+        assert_eq!(NumericStatistic::Mean.compute(&[1.0, 2.0, 3.0]), 2.0);
+        assert!(NumericStatistic::Mean.compute(&[]).is_nan());
+        assert!(Bootstrap::default().run_on(&[], |_| 0.0).is_err());
+    }
+}
@@ -0,0 +1,568 @@
+use std::fmt::Write;
+use crate::analysis::{AnswerDistribution, CrossTab, WeightedDistribution};
+use crate::survey::QuestionType;
+
+/// Width of a rendered chart in pixels
+const CHART_WIDTH: f64 = 640.0;
+
+/// Width reserved for bar labels
+const LABEL_WIDTH: f64 = 220.0;
+
+/// Height of one bar including spacing
+const BAR_HEIGHT: f64 = 24.0;
+
+/// Space above the bars for the title
+const TITLE_HEIGHT: f64 = 32.0;
+
+/// Height of one row of legend entries
+const LEGEND_HEIGHT: f64 = 20.0;
+
+/// Height of the histogram plot area
+const HISTOGRAM_HEIGHT: f64 = 240.0;
+
+/// Space left of the histogram for the count axis
+const AXIS_WIDTH: f64 = 56.0;
+
+/// Label used for the bucket that collects options beyond the top N
+const OTHER_LABEL: &str = "Other";
+
+/// Partial block characters for eighths of a terminal cell
+const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Bar fill color
+const BAR_COLOR: &str = "#4c78a8";
+
+/// Fill colors for stacked series, reused in order when there are more series
+const PALETTE: [&str; 10] = [
+    "#4c78a8", "#f58518", "#e45756", "#72b7b2", "#54a24b",
+    "#eeca3b", "#b279a2", "#ff9da6", "#9d755d", "#bab0ac",
+];
+
+/// Horizontal bar chart of labelled values
+#[derive(Debug, Clone)]
+pub struct BarChart {
+    pub title: String,
+    pub bars: Vec<(String, f64)>,
+    pub unit: String, // appended to value labels, e.g. "%"
+    pub overlapping: bool, // values are multiple-choice shares, which don't add up to 100%
+}
+
+impl BarChart {
+    /// Percentage share of each option, largest first
+    pub fn from_distribution(distribution: &AnswerDistribution) -> Self {
+        let mut bars: Vec<(String, f64)> = distribution
+            .distribution
+            .iter()
+            .map(|(option, (_, percentage))| (option.clone(), *percentage))
+            .collect();
+        bars.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            title: distribution.question_text.clone(),
+            bars,
+            unit: "%".to_string(),
+            overlapping: distribution.question_type == QuestionType::MultipleChoice,
+        }
+    }
+
+    /// Weighted percentage share of each option, largest first
+    pub fn from_weighted_distribution(distribution: &WeightedDistribution) -> Self {
+        let mut bars: Vec<(String, f64)> = distribution
+            .distribution
+            .iter()
+            .map(|(option, (_, percentage))| (option.clone(), *percentage))
+            .collect();
+        bars.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            title: distribution.question_text.clone(),
+            bars,
+            unit: "%".to_string(),
+            overlapping: distribution.question_type == QuestionType::MultipleChoice,
+        }
+    }
+
+    /// Keep only the N largest bars
+    pub fn top(mut self, n: usize) -> Self {
+        self.bars.truncate(n);
+        self
+    }
+
+    /// Keep the N largest bars and sum the rest into an "Other" bar.
+    /// Overlapping shares can't be summed, so multiple-choice charts just keep the top N.
+    pub fn top_with_other(mut self, n: usize) -> Self {
+        if self.overlapping {
+            return self.top(n);
+        }
+        if self.bars.len() > n {
+            let other: f64 = self.bars.drain(n..).map(|(_, value)| value).sum();
+            self.bars.push((OTHER_LABEL.to_string(), other));
+        }
+        self
+    }
+
+    /// Render the chart as Unicode block bars fitting in `width` terminal columns
+    pub fn to_text(&self, width: usize) -> String {
+        let values: Vec<String> = self.bars.iter().map(|(_, value)| format!("{:.1}{}", value, self.unit)).collect();
+        let value_width = values.iter().map(|v| v.chars().count()).max().unwrap_or(0);
+        // Labels get at most a third of the line; bars always get at least 10 columns
+        let label_width = self
+            .bars
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(width / 3)
+            .max(1);
+        let bar_width = width.saturating_sub(label_width + value_width + 3).max(10);
+        let max = self.bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+
+        let mut output = format!("{}\n", self.title);
+        for ((label, value), formatted) in self.bars.iter().zip(&values) {
+            let eighths = if max > 0.0 { (value / max * (bar_width * 8) as f64).round() as usize } else { 0 };
+            let mut bar = "█".repeat(eighths / 8);
+            if eighths % 8 > 0 {
+                bar.push(PARTIAL_BLOCKS[eighths % 8]);
+            }
+            let _ = writeln!(
+                output,
+                "{:<label_width$} │{:<bar_width$} {:>value_width$}",
+                fit_label(label, label_width),
+                bar,
+                formatted,
+            );
+        }
+        output
+    }
+
+    /// Render the chart as a standalone SVG element
+    pub fn to_svg(&self) -> String {
+        let height = TITLE_HEIGHT + self.bars.len() as f64 * BAR_HEIGHT + 8.0;
+        let plot_width = CHART_WIDTH - LABEL_WIDTH - 60.0;
+        let max = self.bars.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+
+        let mut svg = svg_open(CHART_WIDTH, height);
+        let _ = writeln!(
+            svg,
+            r#"<text x="0" y="20" font-size="14" font-weight="bold">{}</text>"#,
+            escape_xml(&self.title)
+        );
+
+        for (i, (label, value)) in self.bars.iter().enumerate() {
+            let y = TITLE_HEIGHT + i as f64 * BAR_HEIGHT;
+            let width = if max > 0.0 { value / max * plot_width } else { 0.0 };
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="end">{}</text>"#,
+                LABEL_WIDTH - 6.0,
+                y + 15.0,
+                escape_xml(&truncate_label(label))
+            );
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {:.1}{}</title></rect>"#,
+                LABEL_WIDTH,
+                y + 3.0,
+                width,
+                BAR_HEIGHT - 6.0,
+                BAR_COLOR,
+                escape_xml(label),
+                value,
+                escape_xml(&self.unit)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="11">{:.1}{}</text>"#,
+                LABEL_WIDTH + width + 4.0,
+                y + 15.0,
+                value,
+                escape_xml(&self.unit)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Horizontal 100% stacked bars, one per row option of a cross-tab
+#[derive(Debug, Clone)]
+pub struct StackedBarChart {
+    pub title: String,
+    pub categories: Vec<String>, // one bar each
+    pub series: Vec<String>, // one segment per bar each
+    pub values: Vec<Vec<f64>>, // [category][series] percentages
+}
+
+impl StackedBarChart {
+    /// Row percentages of a cross-tab, keeping its largest-first row order
+    pub fn from_cross_tab(crosstab: &CrossTab) -> Self {
+        Self {
+            title: format!("{} by {}", crosstab.row_question_text, crosstab.column_question_text),
+            categories: crosstab.row_options.clone(),
+            series: crosstab.column_options.clone(),
+            values: crosstab.row_percentages(),
+        }
+    }
+
+    /// Keep only the first N bars
+    pub fn top(mut self, n: usize) -> Self {
+        self.categories.truncate(n);
+        self.values.truncate(n);
+        self
+    }
+
+    /// Render the chart as a standalone SVG element with a legend
+    pub fn to_svg(&self) -> String {
+        let legend = legend_layout(&self.series);
+        let legend_rows = legend.last().map_or(0, |(_, row)| row + 1);
+        let top = TITLE_HEIGHT + legend_rows as f64 * LEGEND_HEIGHT;
+        let height = top + self.categories.len() as f64 * BAR_HEIGHT + 8.0;
+        let plot_width = CHART_WIDTH - LABEL_WIDTH - 20.0;
+
+        let mut svg = svg_open(CHART_WIDTH, height);
+        let _ = writeln!(
+            svg,
+            r#"<text x="0" y="20" font-size="14" font-weight="bold">{}</text>"#,
+            escape_xml(&self.title)
+        );
+
+        for (j, (series, (x, row))) in self.series.iter().zip(&legend).enumerate() {
+            let y = TITLE_HEIGHT + *row as f64 * LEGEND_HEIGHT;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="12" height="12" fill="{}"/><text x="{:.1}" y="{:.1}" font-size="11">{}</text>"#,
+                x,
+                y,
+                PALETTE[j % PALETTE.len()],
+                x + 16.0,
+                y + 10.0,
+                escape_xml(&truncate_label(series))
+            );
+        }
+
+        for (i, (category, values)) in self.categories.iter().zip(&self.values).enumerate() {
+            let y = top + i as f64 * BAR_HEIGHT;
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="end">{}</text>"#,
+                LABEL_WIDTH - 6.0,
+                y + 15.0,
+                escape_xml(&truncate_label(category))
+            );
+
+            // Scale by the row's own total so rounding never overflows the plot
+            let total: f64 = values.iter().sum();
+            let mut x = LABEL_WIDTH;
+            for (j, (series, value)) in self.series.iter().zip(values).enumerate() {
+                let width = if total > 0.0 { value / total * plot_width } else { 0.0 };
+                if width <= 0.0 {
+                    continue;
+                }
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{} / {}: {:.1}%</title></rect>"#,
+                    x,
+                    y + 3.0,
+                    width,
+                    BAR_HEIGHT - 6.0,
+                    PALETTE[j % PALETTE.len()],
+                    escape_xml(category),
+                    escape_xml(series),
+                    value
+                );
+                x += width;
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// One histogram bin covering `lower..upper`; the last bin also includes `upper`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: f64, // weighted when built from weighted values
+}
+
+/// Histogram of the answers to a numeric question
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub title: String,
+    pub bins: Vec<HistogramBin>,
+}
+
+impl Histogram {
+    /// Sort `(value, weight)` pairs into equal-width bins; without a bin count, Sturges' rule picks one
+    pub fn from_values(title: impl Into<String>, values: &[(f64, f64)], bins: Option<usize>) -> Self {
+        let title = title.into();
+        if values.is_empty() {
+            return Self { title, bins: Vec::new() };
+        }
+
+        let min = values.iter().map(|(v, _)| *v).fold(f64::INFINITY, f64::min);
+        let max = values.iter().map(|(v, _)| *v).fold(f64::NEG_INFINITY, f64::max);
+        let bin_count = if min == max {
+            1
+        } else {
+            bins.unwrap_or_else(|| (values.len() as f64).log2().ceil() as usize + 1).max(1)
+        };
+        let width = (max - min) / bin_count as f64;
+
+        let mut bins: Vec<HistogramBin> = (0..bin_count)
+            .map(|i| HistogramBin {
+                lower: min + i as f64 * width,
+                upper: if i + 1 == bin_count { max } else { min + (i + 1) as f64 * width },
+                count: 0.0,
+            })
+            .collect();
+        for (value, weight) in values {
+            let index = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+            bins[index.min(bin_count - 1)].count += weight;
+        }
+
+        Self { title, bins }
+    }
+
+    /// Render the histogram as a standalone SVG element
+    pub fn to_svg(&self) -> String {
+        let height = TITLE_HEIGHT + HISTOGRAM_HEIGHT + 28.0;
+        let plot_width = CHART_WIDTH - AXIS_WIDTH - 16.0;
+        let baseline = TITLE_HEIGHT + HISTOGRAM_HEIGHT;
+        let max = self.bins.iter().map(|bin| bin.count).fold(0.0, f64::max);
+        let bin_width = plot_width / self.bins.len().max(1) as f64;
+
+        let mut svg = svg_open(CHART_WIDTH, height);
+        let _ = writeln!(
+            svg,
+            r#"<text x="0" y="20" font-size="14" font-weight="bold">{}</text>"#,
+            escape_xml(&self.title)
+        );
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{0:.1}" y1="{1:.1}" x2="{0:.1}" y2="{2:.1}" stroke="#888"/><line x1="{0:.1}" y1="{2:.1}" x2="{3:.1}" y2="{2:.1}" stroke="#888"/>"##,
+            AXIS_WIDTH,
+            TITLE_HEIGHT,
+            baseline,
+            AXIS_WIDTH + plot_width
+        );
+        for (value, y) in [(max, TITLE_HEIGHT + 4.0), (0.0, baseline)] {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="11" text-anchor="end">{}</text>"#,
+                AXIS_WIDTH - 6.0,
+                y,
+                format_number(value)
+            );
+        }
+
+        for (i, bin) in self.bins.iter().enumerate() {
+            let bar_height = if max > 0.0 { bin.count / max * HISTOGRAM_HEIGHT } else { 0.0 };
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{} to {}: {}</title></rect>"#,
+                AXIS_WIDTH + i as f64 * bin_width,
+                baseline - bar_height,
+                (bin_width - 1.0).max(1.0),
+                bar_height,
+                BAR_COLOR,
+                format_number(bin.lower),
+                format_number(bin.upper),
+                format_number(bin.count)
+            );
+        }
+
+        // Label at most about eight bin edges so the axis stays readable
+        let step = self.bins.len().div_ceil(8).max(1);
+        for (i, edge) in self.bins.iter().map(|bin| bin.lower).chain(self.bins.last().map(|bin| bin.upper)).enumerate() {
+            if i % step != 0 && i != self.bins.len() {
+                continue;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="11" text-anchor="middle">{}</text>"#,
+                AXIS_WIDTH + i as f64 * bin_width,
+                baseline + 16.0,
+                format_number(edge)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn svg_open(width: f64, height: f64) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.0} {:.0}\" font-family=\"sans-serif\">\n",
+        width, height, width, height
+    )
+}
+
+/// Position legend entries left to right, wrapping onto new rows: (x, row) per label
+fn legend_layout(labels: &[String]) -> Vec<(f64, usize)> {
+    let mut positions = Vec::with_capacity(labels.len());
+    let (mut x, mut row) = (0.0, 0);
+    for label in labels {
+        let width = 16.0 + truncate_label(label).chars().count() as f64 * 6.5 + 14.0;
+        if x > 0.0 && x + width > CHART_WIDTH {
+            x = 0.0;
+            row += 1;
+        }
+        positions.push((x, row));
+        x += width;
+    }
+    positions
+}
+
+/// Format an axis or tooltip number without trailing zeros
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 || value.abs() >= 100.0 {
+        format!("{:.0}", value)
+    } else {
+        let formatted = format!("{:.2}", value);
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// Shorten long option labels so they fit the label column
+fn truncate_label(label: &str) -> String {
+    fit_label(label, 34)
+}
+
+/// Cut a label to at most `max_chars` characters, marking the cut with an ellipsis
+fn fit_label(label: &str, max_chars: usize) -> String {
+    if label.chars().count() <= max_chars {
+        label.to_string()
+    } else {
+        format!("{}…", label.chars().take(max_chars.saturating_sub(1)).collect::<String>())
+    }
+}
+
+/// Escape text for use in XML/HTML content and attributes
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_chart_svg() {
+        let chart = BarChart {
+            title: "Languages <2024>".to_string(),
+            bars: vec![("Rust".to_string(), 60.0), ("C & C++".to_string(), 30.0)],
+            unit: "%".to_string(),
+            overlapping: false,
+        };
+        let svg = chart.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.contains("Languages &lt;2024&gt;"));
+        assert!(svg.contains("C &amp; C++"));
+        // The largest bar spans the whole plot width, the others are proportional
+        assert!(svg.contains(r#"width="360.0""#));
+        assert!(svg.contains(r#"width="180.0""#));
+    }
+
+    #[test]
+    fn test_bar_chart_text() {
+        let chart = BarChart {
+            title: "Languages".to_string(),
+            bars: vec![
+                ("JavaScript and TypeScript".to_string(), 50.0),
+                ("Rust".to_string(), 25.0),
+                ("Go".to_string(), 15.0),
+                ("C".to_string(), 10.0),
+            ],
+            unit: "%".to_string(),
+            overlapping: false,
+        }
+        .top_with_other(2);
+        assert_eq!(chart.bars.last().unwrap(), &("Other".to_string(), 25.0));
+
+        let text = chart.to_text(40);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "Languages");
+        // 40 columns: 13 for the label, 5 for the value, 19 for the bar
+        assert_eq!(lines[1], format!("JavaScript a… │{} 50.0%", "█".repeat(19)));
+        assert_eq!(lines[2], format!("Rust          │{}▌{} 25.0%", "█".repeat(9), " ".repeat(9)));
+        assert!(lines.iter().skip(1).all(|line| line.chars().count() == 40));
+
+        // Nothing to collect when the chart is already short enough
+        assert_eq!(chart.clone().top_with_other(5).bars.len(), 3);
+    }
+
+    #[test]
+    fn test_multiple_choice_bars_have_no_other() {
+        // Respondents picked several languages, so the dropped shares overlap and sum past 100%
+        let chart = BarChart {
+            title: "Languages".to_string(),
+            bars: vec![
+                ("JavaScript".to_string(), 65.0),
+                ("Python".to_string(), 50.0),
+                ("SQL".to_string(), 50.0),
+                ("TypeScript".to_string(), 40.0),
+                ("Bash".to_string(), 35.0),
+            ],
+            unit: "%".to_string(),
+            overlapping: true,
+        }
+        .top_with_other(2);
+        assert_eq!(chart.bars, vec![("JavaScript".to_string(), 65.0), ("Python".to_string(), 50.0)]);
+        assert!(chart.to_text(40).lines().nth(1).unwrap().contains(&"█".repeat(19)));
+    }
+
+    #[test]
+    fn test_stacked_bar_chart_and_histogram() {
+        let chart = StackedBarChart {
+            title: "Remote by Country".to_string(),
+            categories: vec!["Germany".to_string(), "India".to_string()],
+            series: vec!["Remote".to_string(), "Hybrid".to_string()],
+            values: vec![vec![75.0, 25.0], vec![0.0, 100.0]],
+        };
+        let svg = chart.to_svg();
+        // Two legend swatches, three non-empty segments
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert!(svg.contains(r#"width="300.0""#));
+        assert!(svg.contains(r#"width="100.0""#));
+        assert!(svg.contains("<title>India / Hybrid: 100.0%</title>"));
+        assert_eq!(chart.top(1).categories, vec!["Germany"]);
+
+        let values: Vec<(f64, f64)> = [1.0, 2.0, 2.5, 4.0, 5.0].iter().map(|v| (*v, 1.0)).collect();
+        let histogram = Histogram::from_values("YearsCode", &values, Some(2));
+        assert_eq!(
+            histogram.bins,
+            vec![
+                HistogramBin { lower: 1.0, upper: 3.0, count: 3.0 },
+                HistogramBin { lower: 3.0, upper: 5.0, count: 2.0 },
+            ]
+        );
+        // Sturges' rule: ceil(log2 5) + 1 bins; the maximum lands in the last bin
+        let histogram = Histogram::from_values("YearsCode", &values, None);
+        let counts: Vec<f64> = histogram.bins.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1.0, 2.0, 0.0, 2.0]);
+        assert_eq!(histogram.to_svg().matches("<rect").count(), 4);
+
+        let constant = Histogram::from_values("Same", &[(7.0, 2.0), (7.0, 0.5)], None);
+        assert_eq!(constant.bins, vec![HistogramBin { lower: 7.0, upper: 7.0, count: 2.5 }]);
+        assert!(Histogram::from_values("Empty", &[], None).bins.is_empty());
+    }
+}
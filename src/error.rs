@@ -8,9 +8,24 @@ pub enum SurveyError {
     #[error("Excel parsing error: {0}")]
     ExcelError(#[from] calamine::Error),
 
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("Excel writing error: {0}")]
+    XlsxWriteError(#[from] rust_xlsxwriter::XlsxError),
+
+    #[error("Zip archive error: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+
     #[error("Question not found with ID: {0}")]
     QuestionNotFound(usize),
 
+    #[error("Question not found: {name}{}", did_you_mean(.suggestions))]
+    QuestionNameNotFound { name: String, suggestions: Vec<String> },
+
+    #[error("Question group not found: {0}")]
+    GroupNotFound(String),
+
     #[error("Invalid question type for operation")]
     InvalidQuestionType,
 
@@ -20,8 +35,26 @@ pub enum SurveyError {
     #[error("Data parsing error: {0}")]
     DataParsingError(String),
 
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    #[error("Data file {path} has changed since the workspace was saved (expected sha256 {expected}, found {actual})")]
+    DataFileChanged { path: String, expected: String, actual: String },
+
+    #[error("Raking did not converge after {0} iterations")]
+    RakingFailed(usize),
+
     #[error("Empty dataset")]
     EmptyDataset,
 }
 
+/// " (did you mean A or B?)" hint appended to unknown-name errors
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [only] => format!(" (did you mean {}?)", only),
+        [rest @ .., last] => format!(" (did you mean {} or {}?)", rest.join(", "), last),
+    }
+}
+
 pub type Result<T> = std::result::Result<T, SurveyError>;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::path::Path;
+use rust_xlsxwriter::{Format, Workbook};
+use crate::error::{SurveyError, Result};
+
+/// File formats rows can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Pick the format from a file extension (`.csv` or `.xlsx`)
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("csv") => Ok(ExportFormat::Csv),
+            Some("xlsx") => Ok(ExportFormat::Xlsx),
+            _ => Err(SurveyError::InvalidParameter(format!(
+                "Cannot export to {}: expected a .csv or .xlsx file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Write a header row and data rows to a CSV or xlsx file (chosen by extension); missing cells stay empty
+pub fn write_rows(path: &Path, headers: &[&str], rows: &[Vec<Option<&str>>]) -> Result<()> {
+    match ExportFormat::from_path(path)? {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path)?;
+            writer.write_record(headers)?;
+            for row in rows {
+                writer.write_record(row.iter().map(|cell| cell.unwrap_or("")))?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Xlsx => {
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet();
+            let bold = Format::new().set_bold();
+
+            for (col, header) in headers.iter().enumerate() {
+                worksheet.write_string_with_format(0, column_number(col)?, *header, &bold)?;
+            }
+            for (r, row) in rows.iter().enumerate() {
+                let row_number = u32::try_from(r + 1)
+                    .map_err(|_| SurveyError::InvalidParameter("Too many rows for an xlsx sheet".to_string()))?;
+                for (col, cell) in row.iter().enumerate() {
+                    // Cells are written as text so exports match the survey answers verbatim
+                    if let Some(value) = cell {
+                        worksheet.write_string(row_number, column_number(col)?, *value)?;
+                    }
+                }
+            }
+            worksheet.set_freeze_panes(1, 0)?;
+            workbook.save(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn column_number(col: usize) -> Result<u16> {
+    u16::try_from(col).map_err(|_| SurveyError::InvalidParameter("Too many columns for an xlsx sheet".to_string()))
+}
@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use serde::{Deserialize, Serialize};
+use crate::survey::{QuestionType, Survey};
+use crate::text;
+
+/// BM25 term frequency saturation
+const BM25_K1: f64 = 1.2;
+
+/// BM25 document length normalization
+const BM25_B: f64 = 0.75;
+
+/// Score multiplier for index terms that merely start with the query term
+const PREFIX_WEIGHT: f64 = 0.8;
+
+/// Score multiplier per edit for fuzzy (typo-tolerant) matches
+const FUZZY_WEIGHT: f64 = 0.5;
+
+/// Suffixes removed by `stem`, longest first, with their replacements
+const SUFFIXES: &[(&str, &str)] = &[
+    ("ational", "ate"),
+    ("ations", "ate"),
+    ("ation", "ate"),
+    ("ments", ""),
+    ("ment", ""),
+    ("ings", ""),
+    ("ing", ""),
+    ("ies", "y"),
+    ("ed", ""),
+    ("ly", ""),
+    ("s", ""),
+];
+
+/// What a search hit points at
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SearchTarget {
+    Question { question_id: usize },
+    Option { question_id: usize, option: String },
+    TextAnswer { question_id: usize, respondent_id: usize },
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub target: SearchTarget,
+    pub text: String,
+    pub score: f64,
+}
+
+impl SearchHit {
+    /// Display the hit as a single line
+    pub fn display(&self) -> String {
+        let location = match &self.target {
+            SearchTarget::Question { question_id } => format!("Question {}", question_id),
+            SearchTarget::Option { question_id, .. } => format!("Option of Q{}", question_id),
+            SearchTarget::TextAnswer { question_id, respondent_id } => {
+                format!("Answer to Q{} by #{}", question_id, respondent_id)
+            }
+        };
+        format!("{:>7.3}  {}: {}", self.score, location, self.text)
+    }
+}
+
+/// Inverted index over question text, answer options and free-text answers
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    documents: Vec<(SearchTarget, String)>,
+    document_lengths: Vec<usize>,
+    average_length: f64,
+    postings: BTreeMap<String, Vec<(usize, usize)>>, // term -> (document, term frequency), sorted for prefix ranges
+    terms_by_length: HashMap<usize, Vec<String>>, // character count -> terms, for fuzzy candidates
+}
+
+impl SearchIndex {
+    /// Index every question, every option and the answers to text questions
+    pub fn build(survey: &Survey) -> Self {
+        let mut documents = Vec::new();
+        for question in survey.questions() {
+            documents.push((SearchTarget::Question { question_id: question.id }, question.text.clone()));
+            for option in &question.options {
+                let target = SearchTarget::Option { question_id: question.id, option: option.clone() };
+                documents.push((target, option.clone()));
+            }
+        }
+        for answer in survey.answers() {
+            let is_text = survey.questions()
+                .get(answer.question_id)
+                .is_some_and(|q| matches!(q.question_type, QuestionType::Text));
+            if is_text {
+                let target = SearchTarget::TextAnswer {
+                    question_id: answer.question_id,
+                    respondent_id: answer.respondent_id,
+                };
+                documents.push((target, answer.value.clone()));
+            }
+        }
+
+        Self::from_documents(documents)
+    }
+
+    /// Index an arbitrary list of documents
+    pub fn from_documents(documents: Vec<(SearchTarget, String)>) -> Self {
+        let mut postings: BTreeMap<String, Vec<(usize, usize)>> = BTreeMap::new();
+        let mut document_lengths = Vec::with_capacity(documents.len());
+
+        for (document, (_, text)) in documents.iter().enumerate() {
+            let terms = index_terms(text);
+            document_lengths.push(terms.len());
+
+            let mut frequencies: HashMap<String, usize> = HashMap::new();
+            for term in terms {
+                *frequencies.entry(term).or_insert(0) += 1;
+            }
+            for (term, frequency) in frequencies {
+                postings.entry(term).or_default().push((document, frequency));
+            }
+        }
+
+        let average_length = if document_lengths.is_empty() {
+            0.0
+        } else {
+            document_lengths.iter().sum::<usize>() as f64 / document_lengths.len() as f64
+        };
+
+        let mut terms_by_length: HashMap<usize, Vec<String>> = HashMap::new();
+        for term in postings.keys() {
+            terms_by_length.entry(term.chars().count()).or_default().push(term.clone());
+        }
+
+        Self { documents, document_lengths, average_length, postings, terms_by_length }
+    }
+
+    /// Number of indexed documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Check if nothing was indexed
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Ranked search; query terms match exactly, by prefix, or within a small edit distance
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_where(query, limit, |_| true)
+    }
+
+    /// Ranked search over the documents whose target passes `keep`, e.g. only questions
+    pub fn search_where(&self, query: &str, limit: usize, keep: impl Fn(&SearchTarget) -> bool) -> Vec<SearchHit> {
+        let n = self.documents.len() as f64;
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+
+        for query_term in query_terms(query) {
+            // Best contribution of this query term to each document
+            let mut best: HashMap<usize, f64> = HashMap::new();
+
+            for (term, weight) in self.matching_terms(&query_term) {
+                let postings = &self.postings[term];
+                let document_frequency = postings.len() as f64;
+                let idf = ((n - document_frequency + 0.5) / (document_frequency + 0.5) + 1.0).ln();
+
+                for &(document, frequency) in postings {
+                    if !keep(&self.documents[document].0) {
+                        continue;
+                    }
+                    let tf = frequency as f64;
+                    let length_ratio = self.document_lengths[document] as f64 / self.average_length.max(1.0);
+                    let bm25 = idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length_ratio));
+                    let entry = best.entry(document).or_insert(0.0);
+                    *entry = entry.max(weight * bm25);
+                }
+            }
+
+            for (document, score) in best {
+                *scores.entry(document).or_insert(0.0) += score;
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .map(|(document, score)| SearchHit {
+                target: self.documents[document].0.clone(),
+                text: self.documents[document].1.clone(),
+                score,
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.text.cmp(&b.text)));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// Index terms matching a query term, with their weights: the exact term, terms it is a prefix
+    /// of (a sorted range), and terms of a similar length within a small edit distance
+    fn matching_terms(&self, query_term: &str) -> Vec<(&str, f64)> {
+        let mut matches = Vec::new();
+        if let Some((term, _)) = self.postings.get_key_value(query_term) {
+            matches.push((term.as_str(), 1.0));
+        }
+
+        let length = query_term.chars().count();
+        if length >= 3 {
+            let longer = self.postings.range::<str, _>((Bound::Excluded(query_term), Bound::Unbounded));
+            for (term, _) in longer.take_while(|(term, _)| term.starts_with(query_term)) {
+                matches.push((term.as_str(), PREFIX_WEIGHT));
+            }
+        }
+
+        let max_edits = max_edits(query_term);
+        if max_edits > 0 {
+            for candidate_length in length - max_edits..=length + max_edits {
+                for term in self.terms_by_length.get(&candidate_length).into_iter().flatten() {
+                    // Exact and prefix matches are already weighted higher
+                    if term.starts_with(query_term) {
+                        continue;
+                    }
+                    let distance = edit_distance(query_term, term);
+                    if distance <= max_edits {
+                        matches.push((term.as_str(), FUZZY_WEIGHT.powi(distance as i32)));
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
+
+/// Typos tolerated for a query term of this length
+fn max_edits(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Stemmed, stop-word-free terms of a query
+fn query_terms(query: &str) -> Vec<String> {
+    text::tokenize(query)
+        .into_iter()
+        .filter(|token| !text::is_stop_word(token))
+        .map(|token| stem(&token))
+        .collect()
+}
+
+/// Stemmed terms of a document; CamelCase column names also contribute their parts
+fn index_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#')) {
+        if word.is_empty() {
+            continue;
+        }
+
+        let parts = split_camel_case(word);
+        if parts.len() > 1 {
+            terms.extend(parts.iter().map(|part| part.to_lowercase()));
+        }
+        terms.push(word.to_lowercase());
+    }
+
+    terms
+        .into_iter()
+        .filter(|term| !text::is_stop_word(term))
+        .map(|term| stem(&term))
+        .collect()
+}
+
+/// Split `LanguageHaveWorkedWith` into `Language`, `Have`, `Worked`, `With` (keeping acronyms like `AI`)
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+
+    for i in 1..chars.len() {
+        let (index, c) = chars[i];
+        let previous = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|(_, n)| n.is_lowercase());
+        if c.is_uppercase() && (previous.is_lowercase() || (previous.is_uppercase() && next_is_lower)) {
+            parts.push(&word[start..index]);
+            start = index;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+/// Strip common English suffixes so `databases` and `database` share a term
+pub fn stem(word: &str) -> String {
+    for (suffix, replacement) in SUFFIXES {
+        if let Some(root) = word.strip_suffix(suffix) {
+            if root.chars().count() >= 3 && !(*suffix == "s" && root.ends_with('s')) {
+                return format!("{}{}", root, replacement);
+            }
+        }
+    }
+    word.to_string()
+}
+
+/// Edit distance counting insertions, deletions, substitutions and adjacent transpositions
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 0..a.len() {
+        current[0] = i + 1;
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                current[j + 1] = current[j + 1].min(before_previous[j - 1] + 1);
+            }
+        }
+        std::mem::swap(&mut before_previous, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> SearchIndex {
+        let option = |question_id: usize, option: &str| {
+            (SearchTarget::Option { question_id, option: option.to_string() }, option.to_string())
+        };
+        SearchIndex::from_documents(vec![
+            (SearchTarget::Question { question_id: 0 }, "DatabaseHaveWorkedWith".to_string()),
+            option(0, "PostgreSQL"),
+            option(0, "MySQL"),
+            (SearchTarget::Question { question_id: 1 }, "LanguageHaveWorkedWith".to_string()),
+            option(1, "Python"),
+            option(1, "Rust"),
+            (SearchTarget::TextAnswer { question_id: 2, respondent_id: 4 }, "Mostly Python scripts and databases".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let index = index();
+
+        let hits = index.search("postgres", 10);
+        assert_eq!(hits[0].target, SearchTarget::Option { question_id: 0, option: "PostgreSQL".to_string() });
+
+        // Typo tolerance
+        let hits = index.search("pyhton", 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].text, "Python");
+
+        // CamelCase columns and stemming: "database" matches the column and "databases" in a text answer
+        let hits = index.search("database", 10);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().any(|h| h.target == SearchTarget::Question { question_id: 0 }));
+
+        assert!(index.search("the", 10).is_empty());
+
+        let questions = index.search_where("python", 10, |target| matches!(target, SearchTarget::Question { .. }));
+        assert!(questions.is_empty());
+        let options = index.search_where("python", 10, |target| matches!(target, SearchTarget::Option { .. }));
+        assert_eq!(options.len(), 1);
+    }
+
+    #[test]
+    fn test_matching_terms() {
+        let index = index();
+        let weight = |query: &str, term: &str| {
+            index.matching_terms(query).into_iter().find(|(t, _)| *t == term).map(|(_, w)| w)
+        };
+        assert_eq!(weight("python", "python"), Some(1.0));
+        assert_eq!(weight("postgres", "postgresql"), Some(PREFIX_WEIGHT));
+        assert_eq!(weight("pyhton", "python"), Some(FUZZY_WEIGHT));
+        assert_eq!(weight("databse", "database"), Some(FUZZY_WEIGHT));
+        // Short terms need an exact or prefix match
+        assert_eq!(weight("rst", "rust"), None);
+        assert_eq!(weight("my", "mysql"), None);
+        // Each matching term is listed once
+        let terms: Vec<&str> = index.matching_terms("pyth").into_iter().map(|(t, _)| t).collect();
+        assert_eq!(terms, vec!["python"]);
+    }
+
+    #[test]
+    fn test_text_helpers() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("pyhton", "python"), 1);
+        assert_eq!(split_camel_case("AISearchHaveWorkedWith"), vec!["AI", "Search", "Have", "Worked", "With"]);
+        assert_eq!(stem("databases"), "database");
+        assert_eq!(stem("class"), "class");
+    }
+}
@@ -1,23 +1,81 @@
+use std::io::{Read, Seek};
 use std::path::Path;
+use std::sync::OnceLock;
 
 pub mod error;
 pub mod survey;
 pub mod analysis;
+pub mod weights;
+pub mod stats;
+pub mod bootstrap;
+pub mod text;
+pub mod index;
+pub mod matcher;
+pub mod export;
+pub mod chart;
+pub mod report;
+pub mod vega;
+pub mod workspace;
+pub mod schema;
+pub mod loader;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 pub use error::SurveyError;
-pub use survey::{Survey, Question, QuestionType, Answer};
-pub use analysis::{AnswerDistribution, Subset};
+pub use survey::{Survey, Question, QuestionGroup, QuestionRef, QuestionType, Answer};
+pub use analysis::{
+    AdmiredDesired, AdmiredDesiredOption, AnswerDistribution, ConfidenceInterval, Correction, CrossTab,
+    IntervalMethod, Migration, NumericSummary, OptionComparison, Subset, SubsetComparison, WeightedDistribution,
+};
+pub use weights::{RakeTarget, Weights};
+pub use bootstrap::{Bootstrap, BootstrapMethod, BootstrapResult, NumericStatistic};
+pub use text::{Keyword, KeywordInContext, TextAnalysis};
+pub use index::{SearchHit, SearchIndex, SearchTarget};
+pub use matcher::{SearchMatch, SearchMode};
+pub use export::ExportFormat;
+pub use chart::{BarChart, Histogram, HistogramBin, StackedBarChart};
+pub use report::{Report, ReportFormat, ReportSection, SubsetSpec};
+pub use vega::ToVegaLite;
+pub use workspace::{DataFile, LoadOptions, SavedSubset, Workspace};
+pub use schema::{SchemaEntry, SurveySchema};
+pub use loader::DataFormat;
 
 /// Main entry point for the Stack Overflow Survey Analyzer library
 pub struct SurveyAnalyzer {
     survey: Survey,
+    index: OnceLock<SearchIndex>, // built on first ranked search
 }
 
 impl SurveyAnalyzer {
     /// Create a new SurveyAnalyzer by loading data from an Excel file
     pub fn from_excel<P: AsRef<Path>>(path: P) -> Result<Self, SurveyError> {
         let survey = Survey::from_excel(path)?;
-        Ok(Self { survey })
+        Ok(Self { survey, index: OnceLock::new() })
+    }
+
+    /// Create a SurveyAnalyzer from a `.xlsx`, `.csv`, `.csv.gz` or `.zip` survey download
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, SurveyError> {
+        let survey = Survey::from_path(path)?;
+        Ok(Self { survey, index: OnceLock::new() })
+    }
+
+    /// Create a SurveyAnalyzer from an Excel file read from any seekable source
+    pub fn from_excel_reader<R: Read + Seek>(reader: R) -> Result<Self, SurveyError> {
+        let survey = Survey::from_excel_reader(reader)?;
+        Ok(Self { survey, index: OnceLock::new() })
+    }
+
+    /// Create a SurveyAnalyzer from the bytes of an Excel file
+    pub fn from_excel_bytes(bytes: &[u8]) -> Result<Self, SurveyError> {
+        let survey = Survey::from_excel_bytes(bytes)?;
+        Ok(Self { survey, index: OnceLock::new() })
+    }
+
+    /// Look up a question by column position or (case-insensitive) column name
+    pub fn get_question(&self, question: impl Into<QuestionRef>) -> Result<&Question, SurveyError> {
+        self.survey.question(question)
     }
 
     /// Get the survey structure (list of questions)
@@ -25,24 +83,179 @@ impl SurveyAnalyzer {
         self.survey.questions()
     }
 
-    /// Search for questions containing the given term
+    /// Get the question families (columns sharing a prefix, e.g. `Language*`)
+    pub fn get_question_groups(&self) -> &[QuestionGroup] {
+        self.survey.question_groups()
+    }
+
+    /// Search questions through the index, best matches first; like `search_questions_ranked`
+    /// but unlimited and returning the questions themselves
     pub fn search_questions(&self, term: &str) -> Vec<&Question> {
-        self.survey.search_questions(term)
+        self.search_questions_ranked(term, usize::MAX)
+            .into_iter()
+            .filter_map(|hit| match hit.target {
+                SearchTarget::Question { question_id } => self.survey.question(question_id).ok(),
+                _ => None,
+            })
+            .collect()
     }
 
-    /// Search for answer options containing the given term
+    /// Search answer options through the index, best matches first, as `(question_id, option)`
     pub fn search_options(&self, term: &str) -> Vec<(usize, &str)> {
-        self.survey.search_options(term)
+        self.search_options_ranked(term, usize::MAX)
+            .into_iter()
+            .filter_map(|hit| match hit.target {
+                SearchTarget::Option { question_id, option } => {
+                    let question = self.survey.question(question_id).ok()?;
+                    let option = question.options.iter().find(|o| **o == option)?;
+                    Some((question_id, option.as_str()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Search questions with a substring, regex or fuzzy term, best matches first
+    pub fn search_questions_with(&self, term: &str, mode: SearchMode) -> Result<Vec<SearchMatch>, SurveyError> {
+        self.survey.search_questions_with(term, mode)
+    }
+
+    /// Search answer options with a substring, regex or fuzzy term, best matches first
+    pub fn search_options_with(&self, term: &str, mode: SearchMode) -> Result<Vec<SearchMatch>, SurveyError> {
+        self.survey.search_options_with(term, mode)
+    }
+
+    /// Ranked full-text search across questions, options and free-text answers.
+    /// Matches stemmed terms exactly, by prefix ("postgres" finds "PostgreSQL") or with small typos.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_index().search(query, limit)
+    }
+
+    /// Ranked full-text search over question texts only
+    pub fn search_questions_ranked(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_index()
+            .search_where(query, limit, |target| matches!(target, SearchTarget::Question { .. }))
+    }
+
+    /// Ranked full-text search over answer options only
+    pub fn search_options_ranked(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        self.search_index()
+            .search_where(query, limit, |target| matches!(target, SearchTarget::Option { .. }))
+    }
+
+    /// Inverted index over the survey, built on first use
+    fn search_index(&self) -> &SearchIndex {
+        self.index.get_or_init(|| SearchIndex::build(&self.survey))
     }
 
     /// Create a subset of respondents based on question and answer option
-    pub fn create_subset(&self, question_id: usize, option: &str) -> Result<Subset, SurveyError> {
-        self.survey.create_subset(question_id, option)
+    pub fn create_subset(&self, question: impl Into<QuestionRef>, option: &str) -> Result<Subset, SurveyError> {
+        self.survey.create_subset(question, option)
     }
 
     /// Get answer distribution for a question
-    pub fn get_distribution(&self, question_id: usize) -> Result<AnswerDistribution, SurveyError> {
-        self.survey.get_distribution(question_id)
+    pub fn get_distribution(&self, question: impl Into<QuestionRef>) -> Result<AnswerDistribution, SurveyError> {
+        self.survey.get_distribution(question)
+    }
+
+    /// Get answer distribution for a question with a confidence interval on every option's share
+    pub fn get_distribution_with_intervals(&self, question: impl Into<QuestionRef>, level: f64, method: IntervalMethod) -> Result<AnswerDistribution, SurveyError> {
+        self.survey.get_distribution(question)?.with_confidence_intervals(level, method)
+    }
+
+    /// Get answer distribution for a question among the respondents of a subset
+    pub fn get_subset_distribution(&self, question: impl Into<QuestionRef>, subset: &Subset) -> Result<AnswerDistribution, SurveyError> {
+        self.survey.get_subset_distribution(question, subset)
+    }
+
+    /// Compare two subsets on a question (Holm-corrected two-proportion z-tests, alpha = 0.05)
+    pub fn compare(&self, question: impl Into<QuestionRef>, left: &Subset, right: &Subset) -> Result<SubsetComparison, SurveyError> {
+        self.survey.compare(question, left, right)
+    }
+
+    /// Compare two subsets on a question with a chosen correction and significance level
+    pub fn compare_with(&self, question: impl Into<QuestionRef>, left: &Subset, right: &Subset, correction: Correction, alpha: f64) -> Result<SubsetComparison, SurveyError> {
+        self.survey.compare_with(question, left, right, correction, alpha)
+    }
+
+    /// Bootstrap a mean or median of a numeric question, optionally within a subset
+    pub fn bootstrap_numeric(&self, question: impl Into<QuestionRef>, subset: Option<&Subset>, statistic: NumericStatistic, bootstrap: &Bootstrap) -> Result<BootstrapResult, SurveyError> {
+        self.survey.bootstrap_numeric(question, subset, statistic, bootstrap)
+    }
+
+    /// Get word and bigram frequencies for a text question, optionally within a subset
+    pub fn get_text_analysis(&self, question: impl Into<QuestionRef>, subset: Option<&Subset>) -> Result<TextAnalysis, SurveyError> {
+        self.survey.text_analysis(question, subset)
+    }
+
+    /// Get the TF-IDF keywords of a subset's answers to a text question
+    pub fn get_text_keywords(&self, question: impl Into<QuestionRef>, subset: &Subset, top: usize) -> Result<Vec<Keyword>, SurveyError> {
+        self.survey.text_keywords(question, subset, top)
+    }
+
+    /// Find a keyword in the answers to a text question, with surrounding context
+    pub fn keyword_in_context(&self, question: impl Into<QuestionRef>, keyword: &str, width: usize, subset: Option<&Subset>) -> Result<Vec<KeywordInContext>, SurveyError> {
+        self.survey.keyword_in_context(question, keyword, width, subset)
+    }
+
+    /// Get the weighted answer distribution for a question
+    pub fn get_weighted_distribution(&self, question: impl Into<QuestionRef>, weights: &Weights) -> Result<WeightedDistribution, SurveyError> {
+        self.survey.get_weighted_distribution(question, weights)
+    }
+
+    /// Cross-tabulate two choice questions, optionally weighting respondents
+    pub fn get_cross_tab(&self, row_question: impl Into<QuestionRef>, column_question: impl Into<QuestionRef>, weights: Option<&Weights>) -> Result<CrossTab, SurveyError> {
+        self.survey.cross_tab(row_question, column_question, weights)
+    }
+
+    /// Summarize a numeric question, optionally weighting respondents
+    pub fn get_numeric_summary(&self, question: impl Into<QuestionRef>, weights: Option<&Weights>) -> Result<NumericSummary, SurveyError> {
+        self.survey.numeric_summary(question, weights)
+    }
+
+    /// Bin the numeric answers to a question into a histogram
+    pub fn get_histogram(&self, question: impl Into<QuestionRef>, bins: Option<usize>, weights: Option<&Weights>) -> Result<Histogram, SurveyError> {
+        self.survey.histogram(question, bins, weights)
+    }
+
+    /// Read per-respondent weights from a numeric column
+    pub fn weights_from_column(&self, question: impl Into<QuestionRef>) -> Result<Weights, SurveyError> {
+        Weights::from_column(&self.survey, question)
+    }
+
+    /// Rake respondent weights to match target marginal distributions
+    pub fn rake_weights(&self, targets: &[RakeTarget], max_iterations: usize) -> Result<Weights, SurveyError> {
+        Weights::rake(&self.survey, targets, max_iterations)
+    }
+
+    /// Create a subset of respondents who chose an option in any question of a group
+    pub fn create_group_subset(&self, group: &str, option: &str) -> Result<Subset, SurveyError> {
+        self.survey.create_group_subset(group, option)
+    }
+
+    /// Get answer distributions for every choice question in a group
+    pub fn get_group_distributions(&self, group: &str) -> Result<Vec<AnswerDistribution>, SurveyError> {
+        self.survey.group_distributions(group)
+    }
+
+    /// Get admired/desired statistics for a `*HaveWorkedWith` / `*WantToWorkWith` question pair
+    pub fn get_admired_desired(&self, have_question: impl Into<QuestionRef>, want_question: impl Into<QuestionRef>) -> Result<AdmiredDesired, SurveyError> {
+        self.survey.admired_desired(have_question, want_question)
+    }
+
+    /// Get admired/desired statistics for every auto-paired question family
+    pub fn get_all_admired_desired(&self) -> Result<Vec<AdmiredDesired>, SurveyError> {
+        self.survey.admired_desired_all()
+    }
+
+    /// Write the original columns of a subset's respondents to a `.csv` or `.xlsx` file (all columns if none given)
+    pub fn export_rows<P: AsRef<Path>>(&self, subset: &Subset, columns: &[QuestionRef], path: P) -> Result<usize, SurveyError> {
+        self.survey.export_rows(subset, columns, path)
+    }
+
+    /// Render a report of distributions, cross-tabs, summaries and comparisons as Markdown or HTML
+    pub fn render_report(&self, report: &Report, format: ReportFormat, weights: Option<&Weights>) -> Result<String, SurveyError> {
+        report.render(&self.survey, format, weights)
     }
 
     /// Get the raw survey data
@@ -53,9 +266,50 @@ impl SurveyAnalyzer {
 
 #[cfg(test)]
 mod tests {
+    use super::SurveyAnalyzer;
+
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_analyzer_creation() {
         // This test will be implemented after we have the core functionality
         assert!(true);
     }
+
+    #[test]
+    fn test_analyzer_from_memory() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_row(0, 0, ["ResponseId", "RemoteWork"]).unwrap();
+        sheet.write_row(1, 0, ["1", "Remote"]).unwrap();
+        sheet.write_row(2, 0, ["2", "Hybrid"]).unwrap();
+        sheet.write_row(3, 0, ["3", "Remote"]).unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let analyzer = SurveyAnalyzer::from_excel_reader(std::io::Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(analyzer.survey().respondent_count(), 3);
+        assert_eq!(analyzer.get_distribution("RemoteWork").unwrap().distribution["Remote"].0, 2);
+
+        let analyzer = SurveyAnalyzer::from_excel_bytes(&bytes).unwrap();
+        assert_eq!(analyzer.create_subset("RemoteWork", "Hybrid").unwrap().respondent_ids, vec![1]);
+
+        assert!(SurveyAnalyzer::from_excel_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_search_uses_index() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.write_row(0, 0, ["ResponseId", "RemoteWork", "DatabaseHaveWorkedWith"]).unwrap();
+        sheet.write_row(1, 0, ["1", "Remote", "PostgreSQL"]).unwrap();
+        sheet.write_row(2, 0, ["2", "Hybrid", "SQLite"]).unwrap();
+        let analyzer = SurveyAnalyzer::from_excel_bytes(&workbook.save_to_buffer().unwrap()).unwrap();
+
+        // Stemmed and CamelCase-split terms, not raw substrings
+        let questions: Vec<usize> = analyzer.search_questions("databases").iter().map(|q| q.id).collect();
+        assert_eq!(questions, vec![2]);
+        assert!(analyzer.search_questions("abase").is_empty());
+
+        assert_eq!(analyzer.search_options("postgres"), vec![(2, "PostgreSQL")]);
+        assert_eq!(analyzer.search_options("hybrd"), vec![(1, "Hybrid")]);
+    }
 }
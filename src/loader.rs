@@ -0,0 +1,183 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use flate2::read::GzDecoder;
+use zip::ZipArchive;
+use crate::error::{SurveyError, Result};
+use crate::schema::SurveySchema;
+use crate::survey::Survey;
+
+/// Survey data file formats `Survey::from_path` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Xlsx,
+    Csv,
+    CsvGz,
+    Zip,
+}
+
+impl DataFormat {
+    /// Pick the format from a file name (`.xlsx`, `.csv`, `.csv.gz` or `.zip`)
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_lowercase();
+        if name.ends_with(".xlsx") {
+            Ok(DataFormat::Xlsx)
+        } else if name.ends_with(".csv") {
+            Ok(DataFormat::Csv)
+        } else if name.ends_with(".csv.gz") {
+            Ok(DataFormat::CsvGz)
+        } else if name.ends_with(".zip") {
+            Ok(DataFormat::Zip)
+        } else {
+            Err(SurveyError::InvalidParameter(format!(
+                "Cannot load {}: expected a .xlsx, .csv, .csv.gz or .zip file",
+                path.display()
+            )))
+        }
+    }
+}
+
+/// Load a survey file of any supported format
+pub(crate) fn load(path: &Path) -> Result<Survey> {
+    match DataFormat::from_path(path)? {
+        DataFormat::Xlsx => Survey::from_excel(path),
+        DataFormat::Csv => Survey::from_csv(path),
+        DataFormat::CsvGz => Survey::from_csv_reader(GzDecoder::new(BufReader::new(File::open(path)?))),
+        DataFormat::Zip => load_zip(BufReader::new(File::open(path)?)),
+    }
+}
+
+/// Load the results file in a zip archive, applying the schema file next to it if there is one
+pub(crate) fn load_zip<R: Read + Seek>(reader: R) -> Result<Survey> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut files = Vec::new();
+    for index in 0..archive.len() {
+        let file = archive.by_index(index)?;
+        if file.is_file() {
+            files.push((file.name().to_string(), file.size()));
+        }
+    }
+    let (results, schema) = discover(&files)?;
+
+    let mut survey = match DataFormat::from_path(Path::new(results))? {
+        DataFormat::Xlsx => {
+            let mut bytes = Vec::new();
+            archive.by_name(results)?.read_to_end(&mut bytes)?;
+            Survey::from_excel_bytes(&bytes)?
+        }
+        DataFormat::CsvGz => Survey::from_csv_reader(GzDecoder::new(archive.by_name(results)?))?,
+        _ => Survey::from_csv_reader(archive.by_name(results)?)?,
+    };
+    if let Some(schema) = schema {
+        survey.apply_schema(&SurveySchema::from_csv_reader(archive.by_name(schema)?)?);
+    }
+    Ok(survey)
+}
+
+/// Pick the results and schema files among an archive's `(name, size)` entries.
+/// The schema is a CSV with "schema" in its name; the results file is the data file with
+/// "results" in its name, else the largest one.
+fn discover(files: &[(String, u64)]) -> Result<(&str, Option<&str>)> {
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or(path).to_lowercase();
+    let candidates: Vec<&(String, u64)> = files
+        .iter()
+        // Skip metadata folders macOS adds to archives
+        .filter(|(name, _)| !name.starts_with("__MACOSX/") && !file_name(name).starts_with('.'))
+        .collect();
+
+    let schema = candidates
+        .iter()
+        .find(|(name, _)| file_name(name).contains("schema") && file_name(name).ends_with(".csv"))
+        .map(|(name, _)| name.as_str());
+
+    let data: Vec<&&(String, u64)> = candidates
+        .iter()
+        .filter(|(name, _)| !file_name(name).contains("schema"))
+        .filter(|(name, _)| {
+            matches!(DataFormat::from_path(Path::new(name)), Ok(DataFormat::Xlsx | DataFormat::Csv | DataFormat::CsvGz))
+        })
+        .collect();
+    let results = data
+        .iter()
+        .max_by_key(|(name, size)| (file_name(name).contains("results"), *size))
+        .map(|(name, _)| name.as_str())
+        .ok_or_else(|| SurveyError::DataParsingError("No survey results file (.csv or .xlsx) found in archive".to_string()))?;
+
+    Ok((results, schema))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use zip::write::SimpleFileOptions;
+    use crate::survey::QuestionType;
+
+    const RESULTS: &str = "ResponseId,MainBranch,LanguageHaveWorkedWith,LanguageWantToWorkWith\n\
+                           1,I am a developer by profession,Rust;Go,Rust\n\
+                           2,I code primarily as a hobby,Python,NA\n\
+                           3,I am a developer by profession,Go,Go;Rust\n";
+
+    #[test]
+    fn test_discover_archive_files() {
+        let files = vec![
+            ("so_2024/README_2024.txt".to_string(), 10),
+            ("so_2024/survey_results_schema.csv".to_string(), 100),
+            ("so_2024/survey_results_public.csv".to_string(), 5_000),
+            ("so_2024/notes.csv".to_string(), 9_000),
+            ("__MACOSX/so_2024/._survey_results_public.csv".to_string(), 9_999),
+        ];
+        assert_eq!(
+            discover(&files).unwrap(),
+            ("so_2024/survey_results_public.csv", Some("so_2024/survey_results_schema.csv"))
+        );
+        assert_eq!(discover(&files[3..4]).unwrap(), ("so_2024/notes.csv", None));
+        assert!(discover(&files[..2]).is_err());
+    }
+
+    #[test]
+    fn test_load_zip_with_schema() {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        let options = SimpleFileOptions::default();
+        writer.start_file("README_2024.txt", options).unwrap();
+        writer.write_all(b"Stack Overflow Developer Survey").unwrap();
+        writer.start_file("survey_results_schema.csv", options).unwrap();
+        writer
+            .write_all(
+                b"qid,qname,question,force_resp,type,selector\n\
+                  QID1,MainBranch,Which of these options best describes you?,TRUE,MC,SAVR\n\
+                  QID2,Language,Which programming languages have you used?,FALSE,MC,MAVR\n",
+            )
+            .unwrap();
+        writer.start_file("survey_results_public.csv", options).unwrap();
+        writer.write_all(RESULTS.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        buffer.set_position(0);
+        let survey = Survey::from_zip_reader(buffer).unwrap();
+        assert_eq!(survey.respondent_count(), 3);
+        let languages = survey.question("LanguageHaveWorkedWith").unwrap();
+        assert_eq!(languages.question_type, QuestionType::MultipleChoice);
+        assert_eq!(languages.options, vec!["Rust", "Go", "Python"]);
+        assert_eq!(survey.find_group("Language").unwrap().question_ids, vec![2, 3]);
+        assert_eq!(survey.get_distribution("MainBranch").unwrap().distribution["I am a developer by profession"].0, 2);
+    }
+
+    #[test]
+    fn test_load_csv_gz_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("survey_results_public.csv.gz");
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(RESULTS.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let survey = Survey::from_path(&path).unwrap();
+        assert_eq!(survey.respondent_count(), 3);
+        assert_eq!(survey.create_subset("MainBranch", "I code primarily as a hobby").unwrap().respondent_ids, vec![1]);
+
+        assert!(matches!(Survey::from_path(dir.path().join("survey.json")), Err(SurveyError::InvalidParameter(_))));
+    }
+}
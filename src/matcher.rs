@@ -0,0 +1,235 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use crate::error::{SurveyError, Result};
+
+/// Bonus for a fuzzy match character directly following the previous match
+const CONSECUTIVE_BONUS: f64 = 1.0;
+
+/// Bonus for a fuzzy match character at the start of a word or CamelCase part
+const WORD_START_BONUS: f64 = 0.75;
+
+/// How a search term is matched against question text or options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    Regex,
+    Fuzzy,
+}
+
+impl FromStr for SearchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "substring" => Ok(SearchMode::Substring),
+            "regex" => Ok(SearchMode::Regex),
+            "fuzzy" => Ok(SearchMode::Fuzzy),
+            _ => Err(format!("Unknown search mode: {} (expected substring, regex or fuzzy)", s)),
+        }
+    }
+}
+
+impl fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchMode::Substring => write!(f, "substring"),
+            SearchMode::Regex => write!(f, "regex"),
+            SearchMode::Fuzzy => write!(f, "fuzzy"),
+        }
+    }
+}
+
+/// Where and how well a term matched a piece of text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextMatch {
+    pub score: f64,               // 0 to 1, higher is better
+    pub ranges: Vec<Range<usize>>, // byte ranges of the matched text
+}
+
+/// A question or option matched by a search term
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub question_id: usize,
+    pub option: Option<String>, // set when an answer option matched
+    pub text: String,
+    pub score: f64,
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl SearchMatch {
+    /// The matched text with every match wrapped in `open` / `close`
+    pub fn highlighted(&self, open: &str, close: &str) -> String {
+        highlight(&self.text, &self.ranges, open, close)
+    }
+}
+
+/// A search term compiled for one search mode (case-insensitive in every mode)
+#[derive(Debug, Clone)]
+pub enum Matcher {
+    Substring(String),
+    Regex(Regex),
+    Fuzzy(Vec<char>),
+}
+
+impl Matcher {
+    /// Compile a search term; fails only for an invalid regular expression
+    pub fn new(term: &str, mode: SearchMode) -> Result<Self> {
+        match mode {
+            SearchMode::Substring => Ok(Matcher::Substring(term.to_lowercase())),
+            SearchMode::Regex => RegexBuilder::new(term)
+                .case_insensitive(true)
+                .build()
+                .map(Matcher::Regex)
+                .map_err(|e| SurveyError::InvalidParameter(format!("Invalid regex: {}", e))),
+            SearchMode::Fuzzy => Ok(Matcher::Fuzzy(
+                term.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect(),
+            )),
+        }
+    }
+
+    /// Match the term against a piece of text
+    pub fn find(&self, text: &str) -> Option<TextMatch> {
+        match self {
+            Matcher::Substring(term) => {
+                let lower = text.to_lowercase();
+                if term.is_empty() || !lower.contains(term.as_str()) {
+                    return None;
+                }
+                let occurrences = lower.matches(term.as_str()).count();
+                let score = (occurrences * term.chars().count()) as f64 / lower.chars().count() as f64;
+                // Lowercasing can shift byte offsets outside ASCII; only highlight when they line up
+                let ranges = if lower.len() == text.len() {
+                    lower.match_indices(term.as_str()).map(|(start, m)| start..start + m.len()).collect()
+                } else {
+                    Vec::new()
+                };
+                Some(TextMatch { score: score.min(1.0), ranges })
+            }
+            Matcher::Regex(regex) => {
+                if !regex.is_match(text) {
+                    return None;
+                }
+                let ranges: Vec<Range<usize>> = regex.find_iter(text).map(|m| m.range()).filter(|r| !r.is_empty()).collect();
+                let matched: usize = ranges.iter().map(|r| text[r.clone()].chars().count()).sum();
+                let score = matched as f64 / text.chars().count().max(1) as f64;
+                Some(TextMatch { score: score.max(f64::EPSILON), ranges })
+            }
+            Matcher::Fuzzy(pattern) => fuzzy_match(pattern, text),
+        }
+    }
+}
+
+/// Subsequence match rewarding consecutive characters and word starts; tries every start position
+fn fuzzy_match(pattern: &[char], text: &str) -> Option<TextMatch> {
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let lower: Vec<char> = chars.iter().map(|(_, c)| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let is_word_start = |i: usize| {
+        i == 0 || !chars[i - 1].1.is_alphanumeric() || (chars[i].1.is_uppercase() && chars[i - 1].1.is_lowercase())
+    };
+
+    let mut best: Option<(f64, Vec<usize>)> = None;
+    for start in (0..lower.len()).filter(|&i| lower[i] == pattern[0]) {
+        let mut positions = vec![start];
+        let mut next = start + 1;
+        for c in &pattern[1..] {
+            match (next..lower.len()).find(|&i| lower[i] == *c) {
+                Some(i) => {
+                    positions.push(i);
+                    next = i + 1;
+                }
+                None => break,
+            }
+        }
+        if positions.len() < pattern.len() {
+            break; // later starts cannot match either
+        }
+
+        let mut score = 0.0;
+        for (k, &i) in positions.iter().enumerate() {
+            score += 1.0;
+            if k > 0 && positions[k - 1] + 1 == i {
+                score += CONSECUTIVE_BONUS;
+            }
+            if is_word_start(i) {
+                score += WORD_START_BONUS;
+            }
+        }
+        let span = positions[positions.len() - 1] - positions[0] + 1;
+        score -= (span - pattern.len()) as f64 * 0.05;
+
+        if best.as_ref().is_none_or(|(best_score, _)| score > *best_score) {
+            best = Some((score, positions));
+        }
+    }
+
+    let (score, positions) = best?;
+    let max_score = pattern.len() as f64 * (1.0 + CONSECUTIVE_BONUS + WORD_START_BONUS);
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for i in positions {
+        let (byte, c) = chars[i];
+        match ranges.last_mut() {
+            Some(range) if range.end == byte => range.end = byte + c.len_utf8(),
+            _ => ranges.push(byte..byte + c.len_utf8()),
+        }
+    }
+
+    Some(TextMatch { score: (score / max_score).clamp(f64::EPSILON, 1.0), ranges })
+}
+
+/// Wrap each byte range of `text` in `open` / `close` markers
+pub fn highlight(text: &str, ranges: &[Range<usize>], open: &str, close: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in ranges {
+        if range.start < last || range.end > text.len() {
+            continue;
+        }
+        output.push_str(&text[last..range.start]);
+        output.push_str(open);
+        output.push_str(&text[range.clone()]);
+        output.push_str(close);
+        last = range.end;
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring_and_regex_modes() {
+        let matcher = Matcher::new("work", SearchMode::Substring).unwrap();
+        let found = matcher.find("LanguageHaveWorkedWith").unwrap();
+        assert_eq!(found.ranges, vec![12..16]);
+        assert!(matcher.find("Country").is_none());
+
+        let matcher = Matcher::new("^(language|database)", SearchMode::Regex).unwrap();
+        let found = matcher.find("DatabaseWantToWorkWith").unwrap();
+        assert_eq!(highlight("DatabaseWantToWorkWith", &found.ranges, "[", "]"), "[Database]WantToWorkWith");
+
+        assert!(matches!(Matcher::new("(", SearchMode::Regex), Err(SurveyError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_fuzzy_mode() {
+        let matcher = Matcher::new("lhww", SearchMode::Fuzzy).unwrap();
+        let found = matcher.find("LanguageHaveWorkedWith").unwrap();
+        assert_eq!(highlight("LanguageHaveWorkedWith", &found.ranges, "[", "]"), "[L]anguage[H]ave[W]orked[W]ith");
+
+        let tight = matcher.find("LanguageHaveWorkedWith").unwrap().score;
+        let loose = matcher.find("Lots of hardware, worldwide").unwrap().score;
+        assert!(tight > loose);
+        assert!(matcher.find("Language").is_none());
+    }
+}
@@ -0,0 +1,312 @@
+//! Python bindings, built with `maturin develop --features python`
+use pyo3::exceptions::{PyKeyError, PyOSError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::analysis::{AnswerDistribution, Correction, IntervalMethod, Subset};
+use crate::error::SurveyError;
+use crate::matcher::SearchMode;
+use crate::survey::QuestionRef;
+use crate::SurveyAnalyzer;
+
+impl From<SurveyError> for PyErr {
+    fn from(error: SurveyError) -> Self {
+        let message = error.to_string();
+        match error {
+            SurveyError::QuestionNotFound(_)
+            | SurveyError::QuestionNameNotFound { .. }
+            | SurveyError::GroupNotFound(_)
+            | SurveyError::OptionNotFound(_) => PyKeyError::new_err(message),
+            SurveyError::InvalidParameter(_) | SurveyError::InvalidQuestionType | SurveyError::EmptyDataset => {
+                PyValueError::new_err(message)
+            }
+            SurveyError::Io(_) | SurveyError::DataFileChanged { .. } => PyOSError::new_err(message),
+            _ => PyRuntimeError::new_err(message),
+        }
+    }
+}
+
+/// A question given from Python as a column position or a column name
+#[derive(FromPyObject)]
+enum PyQuestion {
+    Index(usize),
+    Name(String),
+}
+
+impl From<PyQuestion> for QuestionRef {
+    fn from(question: PyQuestion) -> Self {
+        match question {
+            PyQuestion::Index(index) => QuestionRef::Index(index),
+            PyQuestion::Name(name) => QuestionRef::from(name),
+        }
+    }
+}
+
+/// Parse a string argument with the type's `FromStr`, raising `ValueError`
+fn parse_arg<T: std::str::FromStr<Err = String>>(value: &str) -> PyResult<T> {
+    value.parse().map_err(PyValueError::new_err)
+}
+
+/// Convert any serializable result to plain Python dicts, lists and scalars
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let value = serde_json::to_value(value).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    json_to_python(py, &value)
+}
+
+fn json_to_python(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => u.into_pyobject(py)?.into_any().unbind(),
+            (None, Some(i)) => i.into_pyobject(py)?.into_any().unbind(),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any().unbind(),
+        },
+        Value::String(s) => s.into_pyobject(py)?.into_any().unbind(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_python(py, item)?)?;
+            }
+            list.into_any().unbind()
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, field) in fields {
+                dict.set_item(key, json_to_python(py, field)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
+
+/// Survey loaded once from a .xlsx, .csv, .csv.gz or .zip file
+#[pyclass(name = "SurveyAnalyzer", module = "so_survey_analyzer", frozen)]
+struct PySurveyAnalyzer {
+    inner: SurveyAnalyzer,
+}
+
+#[pymethods]
+impl PySurveyAnalyzer {
+    #[new]
+    fn new(py: Python<'_>, path: std::path::PathBuf) -> PyResult<Self> {
+        // Loading a full survey takes seconds; let other Python threads run meanwhile
+        let inner = py.allow_threads(|| SurveyAnalyzer::from_path(&path))?;
+        Ok(Self { inner })
+    }
+
+    /// Load from the contents of an Excel file, e.g. an HTTP response body
+    #[staticmethod]
+    fn from_bytes(py: Python<'_>, data: &[u8]) -> PyResult<Self> {
+        let inner = py.allow_threads(|| SurveyAnalyzer::from_excel_bytes(data))?;
+        Ok(Self { inner })
+    }
+
+    /// Number of respondents
+    #[getter]
+    fn respondent_count(&self) -> usize {
+        self.inner.survey().respondent_count()
+    }
+
+    /// All questions as dicts
+    fn structure(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner.get_survey_structure())
+    }
+
+    /// One question as a dict
+    fn question(&self, py: Python<'_>, question: PyQuestion) -> PyResult<PyObject> {
+        to_python(py, self.inner.get_question(question)?)
+    }
+
+    /// Ranked question matches: `mode` is substring, regex or fuzzy
+    #[pyo3(signature = (term, mode = "substring", options = false))]
+    fn search(&self, py: Python<'_>, term: &str, mode: &str, options: bool) -> PyResult<PyObject> {
+        let mode: SearchMode = parse_arg(mode)?;
+        let matches = if options {
+            self.inner.search_options_with(term, mode)?
+        } else {
+            self.inner.search_questions_with(term, mode)?
+        };
+        to_python(py, &matches)
+    }
+
+    /// Respondents who chose `option`
+    fn subset(&self, question: PyQuestion, option: &str) -> PyResult<PySubset> {
+        Ok(PySubset { inner: self.inner.create_subset(question, option)? })
+    }
+
+    /// Answer distribution, optionally within a subset or with confidence intervals at level `ci`
+    #[pyo3(signature = (question, subset = None, ci = None, ci_method = "wilson"))]
+    fn distribution(
+        &self,
+        question: PyQuestion,
+        subset: Option<PyRef<'_, PySubset>>,
+        ci: Option<f64>,
+        ci_method: &str,
+    ) -> PyResult<PyAnswerDistribution> {
+        let inner = match (subset, ci) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err("Confidence intervals are not available for subset distributions"))
+            }
+            (Some(subset), None) => self.inner.get_subset_distribution(question, &subset.inner)?,
+            (None, Some(level)) => {
+                let method: IntervalMethod = parse_arg(ci_method)?;
+                self.inner.get_distribution_with_intervals(question, level, method)?
+            }
+            (None, None) => self.inner.get_distribution(question)?,
+        };
+        Ok(PyAnswerDistribution { inner })
+    }
+
+    /// Cross-tabulation of two questions as a dict
+    fn crosstab(&self, py: Python<'_>, row: PyQuestion, column: PyQuestion) -> PyResult<PyObject> {
+        to_python(py, &self.inner.get_cross_tab(row, column, None)?)
+    }
+
+    /// Per-option comparison of two subsets as a dict; `correction` is holm, bh or none
+    #[pyo3(signature = (question, left, right, correction = "holm", alpha = 0.05))]
+    fn compare(
+        &self,
+        py: Python<'_>,
+        question: PyQuestion,
+        left: PyRef<'_, PySubset>,
+        right: PyRef<'_, PySubset>,
+        correction: &str,
+        alpha: f64,
+    ) -> PyResult<PyObject> {
+        let correction: Correction = parse_arg(correction)?;
+        to_python(py, &self.inner.compare_with(question, &left.inner, &right.inner, correction, alpha)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<SurveyAnalyzer: {} questions, {} respondents>",
+            self.inner.get_survey_structure().len(),
+            self.inner.survey().respondent_count()
+        )
+    }
+}
+
+/// Respondents selected by an answer, combinable with `&`, `|` and `-`
+#[pyclass(name = "Subset", module = "so_survey_analyzer", frozen)]
+struct PySubset {
+    inner: Subset,
+}
+
+#[pymethods]
+impl PySubset {
+    #[getter]
+    fn question_id(&self) -> usize {
+        self.inner.question_id
+    }
+
+    /// Question group the option was matched across, or `None` for a single question
+    #[getter]
+    fn group(&self) -> Option<&str> {
+        self.inner.group.as_deref()
+    }
+
+    #[getter]
+    fn option(&self) -> &str {
+        &self.inner.option
+    }
+
+    #[getter]
+    fn respondent_ids(&self) -> Vec<usize> {
+        self.inner.respondent_ids.clone()
+    }
+
+    /// Share of all respondents, in percent
+    #[getter]
+    fn percentage(&self) -> f64 {
+        self.inner.percentage()
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.size()
+    }
+
+    fn __and__(&self, other: PyRef<'_, PySubset>) -> PySubset {
+        PySubset { inner: self.inner.intersection(&other.inner) }
+    }
+
+    fn __or__(&self, other: PyRef<'_, PySubset>) -> PySubset {
+        PySubset { inner: self.inner.union(&other.inner) }
+    }
+
+    fn __sub__(&self, other: PyRef<'_, PySubset>) -> PySubset {
+        PySubset { inner: self.inner.difference(&other.inner) }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Subset {}: {} respondents>", self.inner.option, self.inner.size())
+    }
+}
+
+/// Counts and percentages of each answer option
+#[pyclass(name = "AnswerDistribution", module = "so_survey_analyzer", frozen)]
+struct PyAnswerDistribution {
+    inner: AnswerDistribution,
+}
+
+#[pymethods]
+impl PyAnswerDistribution {
+    #[getter]
+    fn question_id(&self) -> usize {
+        self.inner.question_id
+    }
+
+    #[getter]
+    fn question_text(&self) -> &str {
+        &self.inner.question_text
+    }
+
+    #[getter]
+    fn total_responses(&self) -> usize {
+        self.inner.total_responses
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_python(py, &self.inner)
+    }
+
+    /// Column lists ready for `pandas.DataFrame(...)`, most common option first
+    fn to_columns<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let mut rows = self.inner.above_threshold(0.0);
+        rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let columns = PyDict::new(py);
+        columns.set_item("option", rows.iter().map(|(option, _, _)| option.as_str()).collect::<Vec<_>>())?;
+        columns.set_item("count", rows.iter().map(|(_, count, _)| *count).collect::<Vec<_>>())?;
+        columns.set_item("percentage", rows.iter().map(|(_, _, percentage)| *percentage).collect::<Vec<_>>())?;
+        if !self.inner.intervals.is_empty() {
+            let bound = |lower: bool| -> Vec<Option<f64>> {
+                rows.iter()
+                    .map(|(option, _, _)| {
+                        self.inner.intervals.get(option.as_str()).map(|ci| if lower { ci.lower } else { ci.upper })
+                    })
+                    .collect()
+            };
+            columns.set_item("ci_lower", bound(true))?;
+            columns.set_item("ci_upper", bound(false))?;
+        }
+        Ok(columns)
+    }
+
+    fn __repr__(&self) -> String {
+        self.inner.display()
+    }
+}
+
+#[pymodule]
+fn so_survey_analyzer(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PySurveyAnalyzer>()?;
+    module.add_class::<PySubset>()?;
+    module.add_class::<PyAnswerDistribution>()?;
+    Ok(())
+}
@@ -0,0 +1,508 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::analysis::Correction;
+use crate::chart::{escape_xml, BarChart, StackedBarChart};
+use crate::error::{SurveyError, Result};
+use crate::survey::{QuestionRef, QuestionType, Survey};
+use crate::weights::Weights;
+
+/// Document format of a rendered report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Pick the format from a file extension (`.md` / `.markdown` or `.html` / `.htm`)
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("md" | "markdown") => Ok(ReportFormat::Markdown),
+            Some("html" | "htm") => Ok(ReportFormat::Html),
+            _ => Err(SurveyError::InvalidParameter(format!(
+                "Cannot write a report to {}: expected a .md or .html file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(format!("Unknown report format: {} (expected markdown or html)", s)),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Markdown => write!(f, "markdown"),
+            ReportFormat::Html => write!(f, "html"),
+        }
+    }
+}
+
+/// Respondents who gave an answer, as used by comparison sections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsetSpec {
+    pub question: QuestionRef,
+    pub option: String,
+}
+
+/// One section of a report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportSection {
+    /// A paragraph of free text
+    Text { text: String },
+    /// Answer distribution with a bar chart
+    Distribution {
+        question: QuestionRef,
+        #[serde(default)]
+        top: Option<usize>, // only the N most common options
+    },
+    /// Row percentages of two choice questions with a stacked bar chart
+    CrossTab { row: QuestionRef, column: QuestionRef },
+    /// Summary statistics of a numeric question with a histogram
+    NumericSummary { question: QuestionRef },
+    /// Two subsets compared on one question with significance tests
+    Comparison {
+        question: QuestionRef,
+        left: SubsetSpec,
+        right: SubsetSpec,
+        #[serde(default = "default_correction", deserialize_with = "from_str")]
+        correction: Correction,
+        #[serde(default = "default_alpha")]
+        alpha: f64,
+    },
+}
+
+fn default_correction() -> Correction {
+    Correction::Holm
+}
+
+fn default_alpha() -> f64 {
+    0.05
+}
+
+/// Deserialize a value through its `FromStr` implementation (e.g. `"bh"` for a correction)
+fn from_str<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+}
+
+/// A report: a title and a list of sections, rendered against a survey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub title: String,
+    #[serde(default)]
+    pub sections: Vec<ReportSection>,
+}
+
+/// A section after its analysis has run, independent of the output format
+struct RenderedSection {
+    heading: String,
+    notes: Vec<String>,
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    chart: Option<String>, // inline SVG
+}
+
+impl Report {
+    /// Create an empty report
+    pub fn new(title: impl Into<String>) -> Self {
+        Self { title: title.into(), sections: Vec::new() }
+    }
+
+    /// Append a section
+    pub fn section(mut self, section: ReportSection) -> Self {
+        self.sections.push(section);
+        self
+    }
+
+    /// Parse a report spec from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| SurveyError::DataParsingError(format!("Invalid report spec: {}", e)))
+    }
+
+    /// Read a report spec from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Run every section against the survey and render a self-contained document.
+    /// With weights, distributions, cross-tabs and numeric summaries are weighted;
+    /// subset comparisons stay unweighted and are noted as such.
+    pub fn render(&self, survey: &Survey, format: ReportFormat, weights: Option<&Weights>) -> Result<String> {
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| render_section(survey, section, weights))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut intro = format!("Based on {} respondents", survey.respondent_count());
+        if let Some(weights) = weights {
+            intro.push_str(&format!(
+                ", weighted (effective sample size {:.1})",
+                weights.effective_sample_size(0..survey.respondent_count())
+            ));
+        }
+        intro.push('.');
+
+        Ok(match format {
+            ReportFormat::Markdown => to_markdown(&self.title, &intro, &sections),
+            ReportFormat::Html => to_html(&self.title, &intro, &sections),
+        })
+    }
+}
+
+fn render_section(survey: &Survey, section: &ReportSection, weights: Option<&Weights>) -> Result<RenderedSection> {
+    let mut rendered = RenderedSection {
+        heading: String::new(),
+        notes: Vec::new(),
+        headers: Vec::new(),
+        rows: Vec::new(),
+        chart: None,
+    };
+
+    match section {
+        ReportSection::Text { text } => rendered.notes.push(text.clone()),
+
+        ReportSection::Distribution { question, top } => {
+            let top = top.unwrap_or(usize::MAX);
+            let (title, question_type, mut bars, total) = match weights {
+                Some(weights) => {
+                    let distribution = survey.get_weighted_distribution(question, weights)?;
+                    let bars: Vec<(String, f64, String)> = distribution
+                        .distribution
+                        .iter()
+                        .map(|(option, (weight, percentage))| (option.clone(), *percentage, format!("{:.1}", weight)))
+                        .collect();
+                    let total = format!("{:.1} (weighted)", distribution.total_weight);
+                    (distribution.question_text, distribution.question_type, bars, total)
+                }
+                None => {
+                    let distribution = survey.get_distribution(question)?;
+                    let bars: Vec<(String, f64, String)> = distribution
+                        .distribution
+                        .iter()
+                        .map(|(option, (count, percentage))| (option.clone(), *percentage, count.to_string()))
+                        .collect();
+                    let total = distribution.total_responses.to_string();
+                    (distribution.question_text, distribution.question_type, bars, total)
+                }
+            };
+            bars.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            bars.truncate(top);
+
+            rendered.heading = format!("Distribution: {}", title);
+            rendered.notes.push(format!("Total responses: {}", total));
+            rendered.headers = vec!["Option".to_string(), "Count".to_string(), "Share".to_string()];
+            rendered.rows = bars
+                .iter()
+                .map(|(option, percentage, count)| vec![option.clone(), count.clone(), format!("{:.1}%", percentage)])
+                .collect();
+            let chart = BarChart {
+                title,
+                bars: bars.into_iter().map(|(option, percentage, _)| (option, percentage)).collect(),
+                unit: "%".to_string(),
+                overlapping: question_type == QuestionType::MultipleChoice,
+            };
+            rendered.chart = Some(chart.to_svg());
+        }
+
+        ReportSection::CrossTab { row, column } => {
+            let crosstab = survey.cross_tab(row, column, weights)?;
+            rendered.heading = format!("{} by {}", crosstab.row_question_text, crosstab.column_question_text);
+            rendered.notes.push(format!("Total responses: {} (row percentages)", crosstab.total_responses));
+            rendered.headers = std::iter::once(String::new())
+                .chain(crosstab.column_options.iter().cloned())
+                .chain(std::iter::once("Total".to_string()))
+                .collect();
+            rendered.rows = crosstab
+                .row_options
+                .iter()
+                .zip(crosstab.row_percentages())
+                .zip(crosstab.row_totals())
+                .map(|((option, percentages), total)| {
+                    std::iter::once(option.clone())
+                        .chain(percentages.iter().map(|p| format!("{:.1}%", p)))
+                        .chain(std::iter::once(format!("{:.0}", total)))
+                        .collect()
+                })
+                .collect();
+            rendered.chart = Some(StackedBarChart::from_cross_tab(&crosstab).to_svg());
+        }
+
+        ReportSection::NumericSummary { question } => {
+            let summary = survey.numeric_summary(question, weights)?;
+            rendered.heading = format!("Summary: {}", summary.question_text);
+            rendered.headers = vec!["Statistic".to_string(), "Value".to_string()];
+            rendered.rows = vec![vec!["Count".to_string(), summary.count.to_string()]];
+            for (name, value) in [
+                ("Mean", summary.mean),
+                ("Std Dev", summary.std_dev),
+                ("Min", summary.min),
+                ("Median", summary.median),
+                ("Max", summary.max),
+            ] {
+                rendered.rows.push(vec![name.to_string(), format!("{:.2}", value)]);
+            }
+            rendered.chart = Some(survey.histogram(question, None, weights)?.to_svg());
+        }
+
+        ReportSection::Comparison { question, left, right, correction, alpha } => {
+            let left = survey.create_subset(&left.question, &left.option)?;
+            let right = survey.create_subset(&right.question, &right.option)?;
+            let comparison = survey.compare_with(question, &left, &right, *correction, *alpha)?;
+
+            rendered.heading = format!(
+                "{}: {} vs {}",
+                comparison.question_text, comparison.left_label, comparison.right_label
+            );
+            rendered.notes.push(format!(
+                "{} respondents vs {}; {} correction, alpha = {}",
+                comparison.left_total, comparison.right_total, comparison.correction, comparison.alpha
+            ));
+            if weights.is_some() {
+                rendered.notes.push("Unweighted: subset comparisons use respondent counts".to_string());
+            }
+            rendered.headers = vec![
+                "Option".to_string(),
+                comparison.left_label.clone(),
+                comparison.right_label.clone(),
+                "Difference".to_string(),
+                "Adjusted p".to_string(),
+                "Significant".to_string(),
+            ];
+            rendered.rows = comparison
+                .options
+                .iter()
+                .map(|o| {
+                    vec![
+                        o.option.clone(),
+                        format!("{:.1}%", o.left_percentage),
+                        format!("{:.1}%", o.right_percentage),
+                        format!("{:+.1} pp", o.difference),
+                        format!("{:.4}", o.adjusted_p_value),
+                        if o.significant { "yes" } else { "" }.to_string(),
+                    ]
+                })
+                .collect();
+        }
+    }
+
+    Ok(rendered)
+}
+
+fn to_markdown(title: &str, intro: &str, sections: &[RenderedSection]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+
+    let mut output = format!("# {}\n\n{}\n\n", title, intro);
+    for section in sections {
+        if !section.heading.is_empty() {
+            output.push_str(&format!("## {}\n\n", section.heading));
+        }
+        for note in &section.notes {
+            output.push_str(&format!("{}\n\n", note));
+        }
+        if !section.headers.is_empty() {
+            let headers: Vec<String> = section.headers.iter().map(|h| cell(h)).collect();
+            output.push_str(&format!("| {} |\n", headers.join(" | ")));
+            let alignment: Vec<&str> = (0..headers.len()).map(|i| if i == 0 { "---" } else { "---:" }).collect();
+            output.push_str(&format!("| {} |\n", alignment.join(" | ")));
+            for row in &section.rows {
+                let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                output.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+            output.push('\n');
+        }
+        if let Some(chart) = &section.chart {
+            output.push_str(chart);
+            output.push('\n');
+        }
+    }
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    output
+}
+
+fn to_html(title: &str, intro: &str, sections: &[RenderedSection]) -> String {
+    let mut output = String::new();
+    output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+    output.push_str(
+        "<style>\n\
+         body { font-family: sans-serif; max-width: 960px; margin: 2em auto; color: #222; }\n\
+         table { border-collapse: collapse; margin: 1em 0; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; }\n\
+         td:not(:first-child) { text-align: right; }\n\
+         th { background: #f3f3f3; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    output.push_str(&format!("<h1>{}</h1>\n<p>{}</p>\n", escape_xml(title), escape_xml(intro)));
+
+    for section in sections {
+        output.push_str("<section>\n");
+        if !section.heading.is_empty() {
+            output.push_str(&format!("<h2>{}</h2>\n", escape_xml(&section.heading)));
+        }
+        for note in &section.notes {
+            output.push_str(&format!("<p>{}</p>\n", escape_xml(note)));
+        }
+        if !section.headers.is_empty() {
+            output.push_str("<table>\n<tr>");
+            for header in &section.headers {
+                output.push_str(&format!("<th>{}</th>", escape_xml(header)));
+            }
+            output.push_str("</tr>\n");
+            for row in &section.rows {
+                output.push_str("<tr>");
+                for cell in row {
+                    output.push_str(&format!("<td>{}</td>", escape_xml(cell)));
+                }
+                output.push_str("</tr>\n");
+            }
+            output.push_str("</table>\n");
+        }
+        if let Some(chart) = &section.chart {
+            output.push_str(chart);
+        }
+        output.push_str("</section>\n");
+    }
+
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::survey::{Answer, Question, QuestionType};
+
+    fn survey() -> Survey {
+        let question = |id: usize, text: &str, question_type: QuestionType| Question {
+            id,
+            text: text.to_string(),
+            question_type,
+            options: vec![],
+        };
+        let mut answers = Vec::new();
+        for respondent_id in 0..10 {
+            let remote = if respondent_id < 6 { "Remote" } else { "In-person" };
+            let language = if respondent_id % 3 == 0 { "Rust" } else { "Go | Python" };
+            for (question_id, value) in [(0, remote), (1, language)] {
+                answers.push(Answer { respondent_id, question_id, value: value.to_string() });
+            }
+            answers.push(Answer { respondent_id, question_id: 2, value: (respondent_id + 1).to_string() });
+        }
+        Survey::from_parts(
+            vec![
+                question(0, "RemoteWork", QuestionType::SingleChoice),
+                question(1, "Language", QuestionType::SingleChoice),
+                question(2, "YearsCode", QuestionType::Numeric),
+            ],
+            answers,
+            10,
+        )
+    }
+
+    #[test]
+    fn test_report_spec_and_rendering() {
+        let report = Report::from_json(
+            r#"{
+                "title": "Weekly <summary>",
+                "sections": [
+                    {"type": "text", "text": "Numbers as of this week."},
+                    {"type": "distribution", "question": "RemoteWork"},
+                    {"type": "cross_tab", "row": "RemoteWork", "column": 1},
+                    {"type": "numeric_summary", "question": "YearsCode"},
+                    {"type": "comparison", "question": "Language",
+                     "left": {"question": "RemoteWork", "option": "Remote"},
+                     "right": {"question": "RemoteWork", "option": "In-person"},
+                     "correction": "bh"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            report.sections[4],
+            ReportSection::Comparison { correction: Correction::BenjaminiHochberg, alpha, .. } if alpha == 0.05
+        ));
+
+        let markdown = report.render(&survey(), ReportFormat::Markdown, None).unwrap();
+        assert!(markdown.starts_with("# Weekly <summary>\n\nBased on 10 respondents."));
+        assert!(markdown.contains("| Remote | 6 | 60.0% |"));
+        assert!(markdown.contains("| Remote | 66.7% | 33.3% | 6 |"));
+        assert!(markdown.contains("| Go \\| Python | 66.7% | 50.0% | +16.7 pp |"));
+        assert!(markdown.contains("| Median | 5.50 |"));
+        assert!(markdown.contains("<title>Remote: 60.0%</title>"));
+
+        let html = report.render(&survey(), ReportFormat::Html, None).unwrap();
+        assert!(html.contains("<h1>Weekly &lt;summary&gt;</h1>"));
+        assert!(html.contains("<td>Remote</td><td>6</td><td>60.0%</td>"));
+        assert!(html.trim_end().ends_with("</html>"));
+
+        let missing = Report::new("Broken").section(ReportSection::NumericSummary { question: "Salary".into() });
+        assert!(missing.render(&survey(), ReportFormat::Markdown, None).is_err());
+        assert!(Report::from_json(r#"{"title": "x", "sections": [{"type": "pie"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_distribution_section_embeds_bar_chart() {
+        let report = Report::new("Remote work")
+            .section(ReportSection::Text { text: "No chart here.".to_string() })
+            .section(ReportSection::Distribution { question: "RemoteWork".into(), top: None });
+        let markdown = report.render(&survey(), ReportFormat::Markdown, None).unwrap();
+        assert_eq!(markdown.matches("<svg").count(), 1);
+        assert!(markdown.contains("<title>In-person: 40.0%</title>"));
+    }
+
+    #[test]
+    fn test_cross_tab_and_summary_sections_embed_charts() {
+        let report = Report::new("Charts")
+            .section(ReportSection::CrossTab { row: "RemoteWork".into(), column: "Language".into() })
+            .section(ReportSection::NumericSummary { question: "YearsCode".into() });
+        let markdown = report.render(&survey(), ReportFormat::Markdown, None).unwrap();
+        assert_eq!(markdown.matches("<svg").count(), 2);
+        // Stacked bar segments for the cross-tab, histogram bins for the summary
+        assert!(markdown.contains("<title>Remote / Rust: 33.3%</title>"));
+        assert!(markdown.lines().any(|line| line.contains("<title>") && line.contains(" to ")));
+
+        // `top` limits a distribution chart to the most common options
+        let top = Report::new("Top").section(ReportSection::Distribution { question: "Language".into(), top: Some(1) });
+        let markdown = top.render(&survey(), ReportFormat::Markdown, None).unwrap();
+        assert!(markdown.contains("<title>Go | Python: 60.0%</title>"));
+        assert!(!markdown.contains("<title>Rust:"));
+    }
+
+    #[test]
+    fn test_weighted_report_notes_unweighted_comparison() {
+        let spec = |option: &str| SubsetSpec { question: "RemoteWork".into(), option: option.to_string() };
+        let report = Report::new("Weighted").section(ReportSection::Comparison {
+            question: "Language".into(),
+            left: spec("Remote"),
+            right: spec("In-person"),
+            correction: Correction::Holm,
+            alpha: 0.05,
+        });
+        let weights = Weights::from_values((1..=10).map(f64::from).collect()).unwrap();
+
+        let markdown = report.render(&survey(), ReportFormat::Markdown, Some(&weights)).unwrap();
+        assert!(markdown.contains(", weighted (effective sample size"));
+        assert!(markdown.contains("Unweighted: subset comparisons use respondent counts"));
+        let markdown = report.render(&survey(), ReportFormat::Markdown, None).unwrap();
+        assert!(!markdown.contains("Unweighted"));
+    }
+}
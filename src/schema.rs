@@ -0,0 +1,109 @@
+use std::io::Read;
+use serde::{Deserialize, Serialize};
+use crate::error::{SurveyError, Result};
+use crate::survey::QuestionType;
+
+/// One row of a survey schema file, e.g. `survey_results_schema.csv` in the SO download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaEntry {
+    /// Column name, or the shared prefix of a family of columns (`Language`)
+    pub name: String,
+    /// Full question wording
+    pub text: String,
+    /// Question kind, e.g. `MC` (multiple choice) or `TE` (text entry)
+    pub kind: String,
+    /// Answer layout, e.g. `SAVR` (single answer) or `MAVR` (multiple answers)
+    pub selector: String,
+}
+
+/// Question names, wording and types published alongside survey results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SurveySchema {
+    pub entries: Vec<SchemaEntry>,
+}
+
+impl SurveySchema {
+    /// Read a schema CSV with `qname`, `question`, `type` and `selector` columns
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let headers: Vec<String> = reader
+            .headers()?
+            .iter()
+            .map(|h| h.trim_start_matches('\u{feff}').trim().to_lowercase())
+            .collect();
+        let column = |name: &str| headers.iter().position(|h| h == name);
+        let name_column = column("qname")
+            .ok_or_else(|| SurveyError::DataParsingError("Schema has no 'qname' column".to_string()))?;
+        let (text_column, kind_column, selector_column) = (column("question"), column("type"), column("selector"));
+
+        let mut entries = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |index: Option<usize>| index.and_then(|i| record.get(i)).unwrap_or("").trim().to_string();
+            let name = field(Some(name_column));
+            if name.is_empty() {
+                continue;
+            }
+            entries.push(SchemaEntry {
+                name,
+                text: field(text_column),
+                kind: field(kind_column),
+                selector: field(selector_column),
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Entry describing a column: an exact name match, else the longest name the column starts with
+    pub fn entry(&self, column: &str) -> Option<&SchemaEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| column.starts_with(&entry.name))
+            .max_by_key(|entry| (entry.name == column, entry.name.len()))
+    }
+
+    /// Question type of a column's multiple-choice entry; other kinds keep the inferred type
+    pub fn question_type(&self, column: &str) -> Option<QuestionType> {
+        let entry = self.entry(column)?;
+        if !entry.kind.eq_ignore_ascii_case("MC") {
+            return None;
+        }
+        if entry.selector.to_uppercase().starts_with("MA") {
+            Some(QuestionType::MultipleChoice)
+        } else {
+            Some(QuestionType::SingleChoice)
+        }
+    }
+
+    /// Family a column belongs to: the schema name it was derived from
+    pub fn family(&self, column: &str) -> Option<String> {
+        self.entry(column).map(|entry| entry.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_lookup() {
+        let csv = "\u{feff}qid,qname,question,force_resp,type,selector\n\
+                   QID1,MainBranch,Which of these options best describes you?,TRUE,MC,SAVR\n\
+                   QID2,Language,Which programming languages have you done extensive work in?,FALSE,MC,MAVR\n\
+                   QID3,LanguageAdmired,Admired languages,FALSE,MC,MAVR\n\
+                   QID4,YearsCode,Including any education,FALSE,TE,SL\n";
+        let schema = SurveySchema::from_csv_reader(csv.as_bytes()).unwrap();
+        assert_eq!(schema.entries.len(), 4);
+
+        assert_eq!(schema.question_type("MainBranch"), Some(QuestionType::SingleChoice));
+        assert_eq!(schema.question_type("LanguageWantToWorkWith"), Some(QuestionType::MultipleChoice));
+        assert_eq!(schema.question_type("YearsCode"), None);
+        assert_eq!(schema.question_type("Country"), None);
+
+        // The longest matching name wins
+        assert_eq!(schema.family("LanguageHaveWorkedWith").as_deref(), Some("Language"));
+        assert_eq!(schema.family("LanguageAdmired").as_deref(), Some("LanguageAdmired"));
+
+        assert!(SurveySchema::from_csv_reader("name,question\nA,B\n".as_bytes()).is_err());
+    }
+}
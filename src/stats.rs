@@ -0,0 +1,319 @@
+/// Standard normal cumulative distribution function
+pub fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Inverse of the standard normal CDF (Acklam's approximation refined by one Halley step)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2,
+        1.38357751867269e2, -3.066479806614716e1, 2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2,
+        6.680131188771972e1, -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838,
+        -2.549732539343734, 4.374664141464968, 2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    let x = if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    let e = normal_cdf(x) - p;
+    let u = e * (2.0 * std::f64::consts::PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
+
+/// Complementary error function (Chebyshev fit, relative error below 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t * (-z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+        .exp();
+
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Natural logarithm of the gamma function (Lanczos approximation)
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.5203681218851, -1259.1392167224028,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507343278686905,
+        -0.13857109526572012, 9.984_369_578_019_572e-6, 1.5056327351493116e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += c / (x + i as f64);
+    }
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b)
+pub fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+
+    // The continued fraction converges quickly only on one side of the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for the incomplete beta function (modified Lentz's method)
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
+/// Quantile of the beta distribution, found by bisection on the regularized incomplete beta
+pub fn beta_quantile(p: f64, a: f64, b: f64) -> f64 {
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if incomplete_beta(a, b, mid) < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Wilson score interval for a binomial proportion
+pub fn wilson_interval(successes: usize, trials: usize, level: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z = normal_quantile(1.0 - (1.0 - level) / 2.0);
+    let z2 = z * z;
+
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let half_width = z / denominator * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ((center - half_width).max(0.0), (center + half_width).min(1.0))
+}
+
+/// Exact (Clopper-Pearson) interval for a binomial proportion
+pub fn clopper_pearson_interval(successes: usize, trials: usize, level: f64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let alpha = 1.0 - level;
+    let x = successes as f64;
+    let n = trials as f64;
+
+    let lower = if successes == 0 {
+        0.0
+    } else {
+        beta_quantile(alpha / 2.0, x, n - x + 1.0)
+    };
+    let upper = if successes >= trials {
+        1.0
+    } else {
+        beta_quantile(1.0 - alpha / 2.0, x + 1.0, n - x)
+    };
+
+    (lower, upper)
+}
+
+/// Two-sided two-proportion z-test with pooled variance, returning (z, p-value)
+pub fn two_proportion_z_test(x1: usize, n1: usize, x2: usize, n2: usize) -> (f64, f64) {
+    if n1 == 0 || n2 == 0 {
+        return (0.0, 1.0);
+    }
+
+    let (n1, n2) = (n1 as f64, n2 as f64);
+    let (p1, p2) = (x1 as f64 / n1, x2 as f64 / n2);
+    let pooled = (x1 + x2) as f64 / (n1 + n2);
+    let standard_error = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    if standard_error == 0.0 {
+        return (0.0, 1.0);
+    }
+
+    let z = (p1 - p2) / standard_error;
+    (z, (2.0 * (1.0 - normal_cdf(z.abs()))).clamp(0.0, 1.0))
+}
+
+/// Holm-Bonferroni step-down adjusted p-values, in input order
+pub fn holm_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    let mut adjusted = vec![0.0; m];
+    let mut running_max: f64 = 0.0;
+    for (rank, &i) in order.iter().enumerate() {
+        running_max = running_max.max(((m - rank) as f64 * p_values[i]).min(1.0));
+        adjusted[i] = running_max;
+    }
+    adjusted
+}
+
+/// Benjamini-Hochberg step-up adjusted p-values (false discovery rate), in input order
+pub fn benjamini_hochberg_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    let mut adjusted = vec![0.0; m];
+    let mut running_min: f64 = 1.0;
+    for (rank, &i) in order.iter().enumerate().rev() {
+        running_min = running_min.min(m as f64 / (rank + 1) as f64 * p_values[i]);
+        adjusted[i] = running_min;
+    }
+    adjusted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn test_normal_distribution() {
+        assert_close(normal_cdf(0.0), 0.5, 1e-7);
+        assert_close(normal_cdf(1.959964), 0.975, 1e-6);
+        assert_close(normal_quantile(0.975), 1.959964, 1e-5);
+        assert_close(normal_quantile(0.005), -2.575829, 1e-5);
+    }
+
+    #[test]
+    fn test_binomial_intervals() {
+        let (lower, upper) = wilson_interval(5, 10, 0.95);
+        assert_close(lower, 0.2366, 1e-4);
+        assert_close(upper, 0.7634, 1e-4);
+
+        let (lower, upper) = clopper_pearson_interval(5, 10, 0.95);
+        assert_close(lower, 0.1871, 1e-4);
+        assert_close(upper, 0.8129, 1e-4);
+
+        let (lower, upper) = clopper_pearson_interval(0, 20, 0.95);
+        assert_eq!(lower, 0.0);
+        assert_close(upper, 0.1684, 1e-4);
+    }
+
+    #[test]
+    fn test_multiple_comparison_corrections() {
+        let p_values = [0.01, 0.04, 0.03, 0.005];
+
+        let holm = holm_adjust(&p_values);
+        for (actual, expected) in holm.iter().zip([0.03, 0.06, 0.06, 0.02]) {
+            assert_close(*actual, expected, 1e-12);
+        }
+
+        let bh = benjamini_hochberg_adjust(&p_values);
+        for (actual, expected) in bh.iter().zip([0.02, 0.04, 0.04, 0.02]) {
+            assert_close(*actual, expected, 1e-12);
+        }
+
+        let (z, p) = two_proportion_z_test(60, 100, 30, 100);
+        assert_close(z, 4.264014, 1e-5);
+        assert!(p < 1e-4);
+    }
+}
@@ -1,11 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io::{Cursor, Read, Seek};
 use std::path::Path;
+use std::str::FromStr;
 use calamine::{Reader, Xlsx, open_workbook};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use crate::error::{SurveyError, Result};
-use crate::analysis::{AnswerDistribution, Subset};
+use crate::analysis::{
+    AdmiredDesired, AdmiredDesiredOption, AnswerDistribution, Correction, CrossTab, Migration, NumericSummary,
+    OptionComparison, Subset, SubsetComparison, WeightedDistribution,
+};
+use crate::bootstrap::{Bootstrap, BootstrapResult, NumericStatistic};
+use crate::chart::Histogram;
+use crate::export;
+use crate::index;
+use crate::loader;
+use crate::schema::SurveySchema;
+use crate::matcher::{Matcher, SearchMatch, SearchMode};
+use crate::stats;
+use crate::text::{self, Keyword, KeywordInContext, TextAnalysis};
+use crate::weights::Weights;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QuestionType {
     SingleChoice,
     MultipleChoice,
@@ -21,6 +37,115 @@ pub struct Question {
     pub options: Vec<String>,
 }
 
+impl Question {
+    /// Add the options in a raw answer to a choice question's option list
+    fn record_options(&mut self, value: &str) {
+        if !matches!(self.question_type, QuestionType::SingleChoice | QuestionType::MultipleChoice) {
+            return;
+        }
+        // Split on common separators for multiple choice
+        let options: Vec<&str> = if value.contains(';') {
+            value.split(';').map(str::trim).collect()
+        } else if value.contains(',') {
+            value.split(',').map(str::trim).collect()
+        } else {
+            vec![value]
+        };
+
+        for option in options {
+            if !self.options.iter().any(|existing| existing == option) {
+                self.options.push(option.to_string());
+            }
+        }
+    }
+}
+
+/// A question given by column position or by column name (e.g. `LanguageHaveWorkedWith`).
+/// Names are stable across survey years; positions are not.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+pub enum QuestionRef {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for QuestionRef {
+    fn from(index: usize) -> Self {
+        QuestionRef::Index(index)
+    }
+}
+
+impl From<&str> for QuestionRef {
+    /// Digits are a column position, anything else a column name
+    fn from(s: &str) -> Self {
+        let s = s.trim();
+        s.parse::<usize>().map_or_else(|_| QuestionRef::Name(s.to_string()), QuestionRef::Index)
+    }
+}
+
+impl From<String> for QuestionRef {
+    fn from(s: String) -> Self {
+        QuestionRef::from(s.as_str())
+    }
+}
+
+impl From<&QuestionRef> for QuestionRef {
+    fn from(question: &QuestionRef) -> Self {
+        question.clone()
+    }
+}
+
+impl<'de> Deserialize<'de> for QuestionRef {
+    /// Numbers are positions; strings go through `From<&str>`, so `"5"` is position 5 as on the command line
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Index(usize),
+            Name(String),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Index(index) => QuestionRef::Index(index),
+            Raw::Name(name) => QuestionRef::from(name),
+        })
+    }
+}
+
+impl FromStr for QuestionRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err("Question name or ID must not be empty".to_string());
+        }
+        Ok(QuestionRef::from(s))
+    }
+}
+
+impl fmt::Display for QuestionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestionRef::Index(index) => write!(f, "{}", index),
+            QuestionRef::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A family of related columns, e.g. `LanguageHaveWorkedWith` and `LanguageWantToWorkWith`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionGroup {
+    pub name: String,
+    pub question_ids: Vec<usize>,
+}
+
+impl QuestionGroup {
+    /// Check if a question belongs to this group
+    pub fn contains(&self, question_id: usize) -> bool {
+        self.question_ids.contains(&question_id)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Answer {
     pub respondent_id: usize,
@@ -28,19 +153,97 @@ pub struct Answer {
     pub value: String,
 }
 
+/// Column suffix of the "have worked with" half of a question family
+const HAVE_WORKED_WITH: &str = "HaveWorkedWith";
+
+/// Column suffix of the "want to work with" half of a question family
+const WANT_TO_WORK_WITH: &str = "WantToWorkWith";
+
+/// Column suffixes shared by the members of a question family
+const FAMILY_SUFFIXES: &[&str] = &[HAVE_WORKED_WITH, WANT_TO_WORK_WITH, "Admired"];
+
+/// Maximum number of "did you mean" suggestions for an unknown question name
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Derive the family name of a column from its shared prefix, e.g. `Knowledge_3` -> `Knowledge`
+fn family_name(column: &str) -> Option<String> {
+    if let Some((prefix, suffix)) = column.rsplit_once('_') {
+        if !prefix.is_empty() && !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) {
+            return Some(prefix.to_string());
+        }
+    }
+
+    FAMILY_SUFFIXES
+        .iter()
+        .filter_map(|suffix| column.strip_suffix(suffix))
+        .find(|prefix| !prefix.is_empty())
+        .map(str::to_string)
+}
+
+/// Group questions sharing a family name, keeping families with at least two members
+fn group_questions<F: Fn(&str) -> Option<String>>(questions: &[Question], key: F) -> Vec<QuestionGroup> {
+    let mut groups: Vec<QuestionGroup> = Vec::new();
+
+    for question in questions {
+        let Some(name) = key(&question.text) else {
+            continue;
+        };
+        match groups.iter_mut().find(|g| g.name == name) {
+            Some(group) => group.question_ids.push(question.id),
+            None => groups.push(QuestionGroup { name, question_ids: vec![question.id] }),
+        }
+    }
+
+    groups.retain(|g| g.question_ids.len() > 1);
+    groups
+}
+
+/// Split a raw answer into its individual options
+pub(crate) fn split_options(value: &str) -> Vec<&str> {
+    let options: Vec<&str> = if value.contains(';') {
+        value.split(';').collect()
+    } else if value.contains(',') {
+        value.split(',').collect()
+    } else {
+        vec![value]
+    };
+
+    options
+        .into_iter()
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct Survey {
     questions: Vec<Question>,
     answers: Vec<Answer>,
+    groups: Vec<QuestionGroup>,
     respondent_count: usize,
 }
 
 impl Survey {
     /// Load survey data from an Excel file
     pub fn from_excel<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut workbook: Xlsx<_> = open_workbook(path)
+        let workbook: Xlsx<_> = open_workbook(path)
             .map_err(calamine::Error::Xlsx)?;
-        
+        Self::from_workbook(workbook)
+    }
+
+    /// Load survey data from an Excel file read from any seekable source (archive entries, HTTP bodies, buffers)
+    pub fn from_excel_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        let workbook = Xlsx::new(reader)
+            .map_err(calamine::Error::Xlsx)?;
+        Self::from_workbook(workbook)
+    }
+
+    /// Load survey data from the bytes of an Excel file, e.g. an upload or a download body
+    pub fn from_excel_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_excel_reader(Cursor::new(bytes))
+    }
+
+    fn from_workbook<R: Read + Seek>(mut workbook: Xlsx<R>) -> Result<Self> {
         // Get the first worksheet (assuming survey data is in the first sheet)
         let worksheet_name = workbook.sheet_names().first()
             .ok_or_else(|| SurveyError::DataParsingError("No worksheets found".to_string()))?
@@ -49,24 +252,57 @@ impl Survey {
         let range = workbook.worksheet_range(&worksheet_name)
             .map_err(calamine::Error::Xlsx)?;
 
+        Self::from_rows(range.rows().map(|row| Ok(row.iter().map(ToString::to_string).collect())))
+    }
+
+    /// Load survey data from a CSV file with a header row
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_csv_reader(std::fs::File::open(path)?)
+    }
+
+    /// Load survey data from CSV read from any source, e.g. a decompressing reader
+    pub fn from_csv_reader<R: Read>(reader: R) -> Result<Self> {
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(reader);
+        Self::from_rows(
+            reader
+                .into_records()
+                .map(|record| Ok(record?.iter().map(str::to_string).collect())),
+        )
+    }
+
+    /// Load survey data from a path, picking the loader by extension:
+    /// `.xlsx`, `.csv`, `.csv.gz` or a `.zip` download with results and schema files
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        loader::load(path.as_ref())
+    }
+
+    /// Load survey data from a zip archive, discovering the results and schema files inside
+    pub fn from_zip_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        loader::load_zip(reader)
+    }
+
+    /// Build questions and answers from a header row followed by one row per respondent
+    fn from_rows<I: IntoIterator<Item = Result<Vec<String>>>>(rows: I) -> Result<Self> {
         let mut questions = Vec::new();
         let mut answers = Vec::new();
-        let mut headers = Vec::new();
         let mut respondent_count = 0;
 
-        // Process the Excel data
-        for (row_idx, row) in range.rows().enumerate() {
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            let row = row?;
             if row_idx == 0 {
                 // Header row - extract question information
                 for (col_idx, cell) in row.iter().enumerate() {
-                    let header = cell.to_string();
-                    headers.push(header.clone());
+                    // CSV exports from Excel start with a byte order mark
+                    let header = cell.trim_start_matches('\u{feff}').to_string();
                     
                     // Create question from header
                     let question = Question {
                         id: col_idx,
-                        text: header.clone(),
                         question_type: Self::infer_question_type(&header),
+                        text: header,
                         options: Vec::new(), // Will be populated as we read answers
                     };
                     questions.push(question);
@@ -75,45 +311,51 @@ impl Survey {
                 // Data row - extract answers
                 respondent_count = respondent_count.max(row_idx);
                 
-                for (col_idx, cell) in row.iter().enumerate() {
-                    let value = cell.to_string().trim().to_string();
+                for (col_idx, cell) in row.into_iter().enumerate() {
+                    let value = cell.trim().to_string();
                     if !value.is_empty() && value != "NA" {
-                        let answer = Answer {
-                            respondent_id: row_idx - 1, // Subtract 1 because we skip header
-                            question_id: col_idx,
-                            value: value.clone(),
-                        };
-                        answers.push(answer);
-
-                        // Update question options for multiple choice questions
                         if let Some(question) = questions.get_mut(col_idx) {
-                            if matches!(question.question_type, QuestionType::SingleChoice | QuestionType::MultipleChoice) {
-                                // Split on common separators for multiple choice
-                                let options: Vec<String> = if value.contains(';') {
-                                    value.split(';').map(|s| s.trim().to_string()).collect()
-                                } else if value.contains(',') {
-                                    value.split(',').map(|s| s.trim().to_string()).collect()
-                                } else {
-                                    vec![value]
-                                };
-
-                                for option in options {
-                                    if !question.options.contains(&option) {
-                                        question.options.push(option);
-                                    }
-                                }
-                            }
+                            question.record_options(&value);
                         }
+                        answers.push(Answer {
+                            respondent_id: row_idx - 1, // Subtract 1 because we skip header
+                            question_id: col_idx,
+                            value,
+                        });
                     }
                 }
             }
         }
 
-        Ok(Survey {
-            questions,
-            answers,
-            respondent_count,
-        })
+        Ok(Self::from_parts(questions, answers, respondent_count))
+    }
+
+    /// Use a survey schema's question types and families in place of the ones inferred from headers
+    pub fn apply_schema(&mut self, schema: &SurveySchema) {
+        let mut retyped = HashSet::new();
+        for question in &mut self.questions {
+            if let Some(question_type) = schema.question_type(&question.text) {
+                if question_type != question.question_type {
+                    question.question_type = question_type;
+                    question.options.clear();
+                    retyped.insert(question.id);
+                }
+            }
+        }
+
+        for answer in &self.answers {
+            if retyped.contains(&answer.question_id) {
+                self.questions[answer.question_id].record_options(&answer.value);
+            }
+        }
+
+        self.regroup(|column| schema.family(column).or_else(|| family_name(column)));
+    }
+
+    /// Assemble a survey from loaded questions and answers, detecting question families
+    pub(crate) fn from_parts(questions: Vec<Question>, answers: Vec<Answer>, respondent_count: usize) -> Self {
+        let groups = group_questions(&questions, family_name);
+        Survey { questions, answers, groups, respondent_count }
     }
 
     /// Infer question type from header text
@@ -136,17 +378,70 @@ impl Survey {
         &self.questions
     }
 
+    /// Look up a question by column position or (case-insensitive) column name
+    pub fn question(&self, question: impl Into<QuestionRef>) -> Result<&Question> {
+        match question.into() {
+            QuestionRef::Index(index) => self.questions.get(index).ok_or(SurveyError::QuestionNotFound(index)),
+            QuestionRef::Name(name) => self.questions
+                .iter()
+                .find(|q| q.text.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| SurveyError::QuestionNameNotFound {
+                    suggestions: self.suggest_questions(&name),
+                    name,
+                }),
+        }
+    }
+
+    /// Resolve a question reference to its column position
+    pub fn resolve_question(&self, question: impl Into<QuestionRef>) -> Result<usize> {
+        self.question(question).map(|q| q.id)
+    }
+
+    /// Column names close to an unknown name: within a few edits, or containing it
+    fn suggest_questions(&self, name: &str) -> Vec<String> {
+        let name = name.to_lowercase();
+        let max_distance = (name.chars().count() / 3).max(2);
+
+        let mut candidates: Vec<(usize, &str)> = self.questions
+            .iter()
+            .filter_map(|q| {
+                let text = q.text.to_lowercase();
+                let distance = index::edit_distance(&name, &text);
+                let contains = name.chars().count() >= 3 && (text.contains(&name) || name.contains(&text));
+                (distance <= max_distance || contains).then_some((distance, q.text.as_str()))
+            })
+            .collect();
+        candidates.sort();
+        candidates.into_iter().take(MAX_SUGGESTIONS).map(|(_, text)| text.to_string()).collect()
+    }
+
     /// Get all answers
     pub fn answers(&self) -> &[Answer] {
         &self.answers
     }
 
+    /// Get the question families detected when loading
+    pub fn question_groups(&self) -> &[QuestionGroup] {
+        &self.groups
+    }
+
+    /// Find a question group by name (case-insensitive)
+    pub fn find_group(&self, name: &str) -> Option<&QuestionGroup> {
+        self.groups.iter().find(|g| g.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Replace the detected groups using a column name -> group name mapping (e.g. from a schema)
+    pub fn regroup<F: Fn(&str) -> Option<String>>(&mut self, key: F) {
+        self.groups = group_questions(&self.questions, key);
+    }
+
     /// Get respondent count
     pub fn respondent_count(&self) -> usize {
         self.respondent_count
     }
 
-    /// Search for questions containing a term
+    /// Search for questions containing a term (a linear scan over every question)
+    #[deprecated(note = "use `SurveyAnalyzer::search_questions`, which looks terms up in the search index")]
     pub fn search_questions(&self, term: &str) -> Vec<&Question> {
         let term_lower = term.to_lowercase();
         self.questions
@@ -155,7 +450,8 @@ impl Survey {
             .collect()
     }
 
-    /// Search for answer options containing a term
+    /// Search for answer options containing a term (a linear scan over every option)
+    #[deprecated(note = "use `SurveyAnalyzer::search_options`, which looks terms up in the search index")]
     pub fn search_options(&self, term: &str) -> Vec<(usize, &str)> {
         let term_lower = term.to_lowercase();
         let mut results = Vec::new();
@@ -171,10 +467,51 @@ impl Survey {
         results
     }
 
+    /// Search question text with a substring, regex or fuzzy term, best matches first
+    pub fn search_questions_with(&self, term: &str, mode: SearchMode) -> Result<Vec<SearchMatch>> {
+        let matcher = Matcher::new(term, mode)?;
+        let mut results: Vec<SearchMatch> = self.questions
+            .iter()
+            .filter_map(|question| {
+                let found = matcher.find(&question.text)?;
+                Some(SearchMatch {
+                    question_id: question.id,
+                    option: None,
+                    text: question.text.clone(),
+                    score: found.score,
+                    ranges: found.ranges,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.question_id.cmp(&b.question_id)));
+        Ok(results)
+    }
+
+    /// Search answer options with a substring, regex or fuzzy term, best matches first
+    pub fn search_options_with(&self, term: &str, mode: SearchMode) -> Result<Vec<SearchMatch>> {
+        let matcher = Matcher::new(term, mode)?;
+        let mut results: Vec<SearchMatch> = self.questions
+            .iter()
+            .flat_map(|question| question.options.iter().map(move |option| (question.id, option)))
+            .filter_map(|(question_id, option)| {
+                let found = matcher.find(option)?;
+                Some(SearchMatch {
+                    question_id,
+                    option: Some(option.clone()),
+                    text: option.clone(),
+                    score: found.score,
+                    ranges: found.ranges,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.question_id.cmp(&b.question_id)));
+        Ok(results)
+    }
+
     /// Create a subset based on question and option
-    pub fn create_subset(&self, question_id: usize, option: &str) -> Result<Subset> {
-        let question = self.questions.get(question_id)
-            .ok_or(SurveyError::QuestionNotFound(question_id))?;
+    pub fn create_subset(&self, question: impl Into<QuestionRef>, option: &str) -> Result<Subset> {
+        let question = self.question(question)?;
+        let question_id = question.id;
 
         let matching_respondents: Vec<usize> = self.answers
             .iter()
@@ -192,20 +529,130 @@ impl Survey {
 
         Ok(Subset {
             question_id,
+            group: None,
             option: option.to_string(),
             respondent_ids: matching_respondents,
             total_respondents: self.respondent_count,
         })
     }
 
+    /// Write the original columns of a subset's respondents to a `.csv` or `.xlsx` file (chosen by extension).
+    /// An empty `columns` list exports every column; repeated columns are written once. Returns the number of rows written.
+    pub fn export_rows<P: AsRef<Path>>(&self, subset: &Subset, columns: &[QuestionRef], path: P) -> Result<usize> {
+        let question_ids: Vec<usize> = if columns.is_empty() {
+            (0..self.questions.len()).collect()
+        } else {
+            let mut question_ids = Vec::with_capacity(columns.len());
+            for column in columns {
+                let id = self.resolve_question(column)?;
+                // A column named twice (e.g. by index and by name) is exported once
+                if !question_ids.contains(&id) {
+                    question_ids.push(id);
+                }
+            }
+            question_ids
+        };
+        let positions: HashMap<usize, usize> = question_ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut respondent_ids = subset.respondent_ids.clone();
+        respondent_ids.sort_unstable();
+        respondent_ids.dedup();
+        let row_of: HashMap<usize, usize> = respondent_ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let mut rows: Vec<Vec<Option<&str>>> = vec![vec![None; question_ids.len()]; respondent_ids.len()];
+        for answer in &self.answers {
+            if let (Some(&row), Some(&column)) = (row_of.get(&answer.respondent_id), positions.get(&answer.question_id)) {
+                rows[row][column] = Some(answer.value.as_str());
+            }
+        }
+
+        let headers: Vec<&str> = question_ids.iter().map(|id| self.questions[*id].text.as_str()).collect();
+        export::write_rows(path.as_ref(), &headers, &rows)?;
+        Ok(rows.len())
+    }
+
     /// Get answer distribution for a question
-    pub fn get_distribution(&self, question_id: usize) -> Result<AnswerDistribution> {
+    pub fn get_distribution(&self, question: impl Into<QuestionRef>) -> Result<AnswerDistribution> {
+        self.distribution_for(self.resolve_question(question)?, None)
+    }
+
+    /// Get answer distribution for a question among the respondents of a subset
+    pub fn get_subset_distribution(&self, question: impl Into<QuestionRef>, subset: &Subset) -> Result<AnswerDistribution> {
+        let question_id = self.resolve_question(question)?;
+        let respondents: HashSet<usize> = subset.respondent_ids.iter().copied().collect();
+        self.distribution_for(question_id, Some(&respondents))
+    }
+
+    /// Compare two subsets on a question using Holm-corrected two-proportion z-tests at alpha = 0.05
+    pub fn compare(&self, question: impl Into<QuestionRef>, left: &Subset, right: &Subset) -> Result<SubsetComparison> {
+        self.compare_with(question, left, right, Correction::Holm, 0.05)
+    }
+
+    /// Compare two subsets on a question with a chosen multiple-comparison correction and significance level
+    pub fn compare_with(&self, question: impl Into<QuestionRef>, left: &Subset, right: &Subset, correction: Correction, alpha: f64) -> Result<SubsetComparison> {
+        let question_id = self.resolve_question(question)?;
+        if !(alpha > 0.0 && alpha < 1.0) {
+            return Err(SurveyError::InvalidParameter(format!("Alpha must be between 0 and 1, got {}", alpha)));
+        }
+
+        let left_distribution = self.get_subset_distribution(question_id, left)?;
+        let right_distribution = self.get_subset_distribution(question_id, right)?;
+        let (left_total, right_total) = (left_distribution.total_responses, right_distribution.total_responses);
+
+        let all_options: BTreeSet<&String> = left_distribution.distribution.keys()
+            .chain(right_distribution.distribution.keys())
+            .collect();
+
+        let mut options: Vec<OptionComparison> = all_options
+            .into_iter()
+            .map(|option| {
+                let (left_count, left_percentage) = left_distribution.distribution.get(option).copied().unwrap_or((0, 0.0));
+                let (right_count, right_percentage) = right_distribution.distribution.get(option).copied().unwrap_or((0, 0.0));
+                let (z_score, p_value) = stats::two_proportion_z_test(left_count, left_total, right_count, right_total);
+                OptionComparison {
+                    option: option.clone(),
+                    left_count,
+                    left_percentage,
+                    right_count,
+                    right_percentage,
+                    difference: left_percentage - right_percentage,
+                    z_score,
+                    p_value,
+                    adjusted_p_value: p_value,
+                    significant: false,
+                }
+            })
+            .collect();
+
+        let p_values: Vec<f64> = options.iter().map(|o| o.p_value).collect();
+        for (option, adjusted) in options.iter_mut().zip(correction.adjust(&p_values)) {
+            option.adjusted_p_value = adjusted;
+            option.significant = adjusted < alpha;
+        }
+        options.sort_by(|a, b| b.difference.abs().total_cmp(&a.difference.abs()).then_with(|| a.option.cmp(&b.option)));
+
+        Ok(SubsetComparison {
+            question_id,
+            question_text: left_distribution.question_text,
+            left_label: left.option.clone(),
+            right_label: right.option.clone(),
+            left_total,
+            right_total,
+            correction,
+            alpha,
+            options,
+        })
+    }
+
+    /// Count answers to a question, optionally restricted to a set of respondents
+    fn distribution_for(&self, question_id: usize, respondents: Option<&HashSet<usize>>) -> Result<AnswerDistribution> {
         let question = self.questions.get(question_id)
             .ok_or(SurveyError::QuestionNotFound(question_id))?;
 
         let question_answers: Vec<&Answer> = self.answers
             .iter()
             .filter(|a| a.question_id == question_id)
+            .filter(|a| respondents.is_none_or(|ids| ids.contains(&a.respondent_id)))
             .collect();
 
         let mut counts = HashMap::new();
@@ -220,20 +667,8 @@ impl Survey {
             }
             QuestionType::MultipleChoice => {
                 for answer in question_answers {
-                    // Split multiple choice answers
-                    let options: Vec<&str> = if answer.value.contains(';') {
-                        answer.value.split(';').collect()
-                    } else if answer.value.contains(',') {
-                        answer.value.split(',').collect()
-                    } else {
-                        vec![answer.value.as_str()]
-                    };
-
-                    for option in options {
-                        let option = option.trim();
-                        if !option.is_empty() {
-                            *counts.entry(option.to_string()).or_insert(0) += 1;
-                        }
+                    for option in split_options(&answer.value) {
+                        *counts.entry(option.to_string()).or_insert(0) += 1;
                     }
                     total_responses += 1;
                 }
@@ -256,16 +691,441 @@ impl Survey {
         Ok(AnswerDistribution {
             question_id,
             question_text: question.text.clone(),
-            question_type: question.question_type.clone(),
+            question_type: question.question_type,
             distribution,
             total_responses,
+            intervals: HashMap::new(),
+        })
+    }
+
+    /// Get the weighted answer distribution for a question
+    pub fn get_weighted_distribution(&self, question: impl Into<QuestionRef>, weights: &Weights) -> Result<WeightedDistribution> {
+        let question = self.question(question)?;
+        let question_id = question.id;
+        if !matches!(question.question_type, QuestionType::SingleChoice | QuestionType::MultipleChoice) {
+            return Err(SurveyError::InvalidQuestionType);
+        }
+
+        let mut counts: HashMap<String, f64> = HashMap::new();
+        let mut total_weight = 0.0;
+        let mut respondents = Vec::new();
+
+        for (respondent_id, options) in self.choices_by_respondent(question) {
+            let weight = weights.get(respondent_id);
+            for option in options {
+                *counts.entry(option.to_string()).or_insert(0.0) += weight;
+            }
+            total_weight += weight;
+            respondents.push(respondent_id);
+        }
+
+        let distribution = counts
+            .into_iter()
+            .map(|(option, weight)| {
+                let percentage = if total_weight > 0.0 { weight / total_weight * 100.0 } else { 0.0 };
+                (option, (weight, percentage))
+            })
+            .collect();
+
+        Ok(WeightedDistribution {
+            question_id,
+            question_text: question.text.clone(),
+            question_type: question.question_type,
+            distribution,
+            total_weight,
+            total_responses: respondents.len(),
+            effective_sample_size: weights.effective_sample_size(respondents),
         })
     }
+
+    /// Cross-tabulate two choice questions, optionally weighting respondents
+    pub fn cross_tab(&self, row_question: impl Into<QuestionRef>, column_question: impl Into<QuestionRef>, weights: Option<&Weights>) -> Result<CrossTab> {
+        let row_question = self.question(row_question)?;
+        let column_question = self.question(column_question)?;
+        let (row_question_id, column_question_id) = (row_question.id, column_question.id);
+        for question in [row_question, column_question] {
+            if !matches!(question.question_type, QuestionType::SingleChoice | QuestionType::MultipleChoice) {
+                return Err(SurveyError::InvalidQuestionType);
+            }
+        }
+
+        let rows = self.choices_by_respondent(row_question);
+        let columns = self.choices_by_respondent(column_question);
+
+        let mut cells: HashMap<(&str, &str), f64> = HashMap::new();
+        let mut row_totals: HashMap<&str, f64> = HashMap::new();
+        let mut column_totals: HashMap<&str, f64> = HashMap::new();
+        let mut respondents = Vec::new();
+
+        for (respondent_id, row_options) in &rows {
+            let Some(column_options) = columns.get(respondent_id) else {
+                continue;
+            };
+            let weight = weights.map_or(1.0, |w| w.get(*respondent_id));
+            for row in row_options {
+                for column in column_options {
+                    *cells.entry((row, column)).or_insert(0.0) += weight;
+                    *row_totals.entry(row).or_insert(0.0) += weight;
+                    *column_totals.entry(column).or_insert(0.0) += weight;
+                }
+            }
+            respondents.push(*respondent_id);
+        }
+
+        let sorted_by_total = |totals: HashMap<&str, f64>| {
+            let mut options: Vec<_> = totals.into_iter().collect();
+            options.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            options.into_iter().map(|(option, _)| option.to_string()).collect::<Vec<_>>()
+        };
+        let row_options = sorted_by_total(row_totals);
+        let column_options = sorted_by_total(column_totals);
+
+        let counts = row_options
+            .iter()
+            .map(|row| {
+                column_options
+                    .iter()
+                    .map(|column| cells.get(&(row.as_str(), column.as_str())).copied().unwrap_or(0.0))
+                    .collect()
+            })
+            .collect();
+
+        Ok(CrossTab {
+            row_question_id,
+            row_question_text: row_question.text.clone(),
+            column_question_id,
+            column_question_text: column_question.text.clone(),
+            row_options,
+            column_options,
+            counts,
+            total_responses: respondents.len(),
+            effective_sample_size: weights.map_or(respondents.len() as f64, |w| w.effective_sample_size(respondents)),
+            weighted: weights.is_some(),
+        })
+    }
+
+    /// Summarize the numeric answers to a question, optionally weighting respondents
+    pub fn numeric_summary(&self, question: impl Into<QuestionRef>, weights: Option<&Weights>) -> Result<NumericSummary> {
+        let question = self.question(question)?;
+        let question_id = question.id;
+
+        let mut values: Vec<(f64, f64, usize)> = self.answers
+            .iter()
+            .filter(|a| a.question_id == question_id)
+            .filter_map(|a| {
+                let value = a.value.parse::<f64>().ok().filter(|v| v.is_finite())?;
+                Some((value, weights.map_or(1.0, |w| w.get(a.respondent_id)), a.respondent_id))
+            })
+            .collect();
+        if values.is_empty() {
+            return Err(SurveyError::EmptyDataset);
+        }
+        values.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let total_weight: f64 = values.iter().map(|(_, w, _)| w).sum();
+        if total_weight <= 0.0 {
+            return Err(SurveyError::EmptyDataset);
+        }
+        let mean = values.iter().map(|(x, w, _)| x * w).sum::<f64>() / total_weight;
+        let squared_deviations: f64 = values.iter().map(|(x, w, _)| w * (x - mean).powi(2)).sum();
+        let std_dev = if total_weight > 1.0 {
+            (squared_deviations / (total_weight - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+
+        // Weighted median: first value whose cumulative weight reaches half the total,
+        // averaged with the next value when it lands exactly on the half
+        let half = total_weight / 2.0;
+        let mut cumulative = 0.0;
+        let mut median = values[values.len() - 1].0;
+        for (i, (x, w, _)) in values.iter().enumerate() {
+            cumulative += w;
+            if (cumulative - half).abs() < 1e-9 * total_weight {
+                median = values.get(i + 1).map_or(*x, |next| (x + next.0) / 2.0);
+                break;
+            }
+            if cumulative > half {
+                median = *x;
+                break;
+            }
+        }
+
+        Ok(NumericSummary {
+            question_id,
+            question_text: question.text.clone(),
+            count: values.len(),
+            total_weight,
+            mean,
+            std_dev,
+            min: values[0].0,
+            median,
+            max: values[values.len() - 1].0,
+            effective_sample_size: weights.map_or(values.len() as f64, |w| {
+                w.effective_sample_size(values.iter().map(|(_, _, id)| *id))
+            }),
+            weighted: weights.is_some(),
+        })
+    }
+
+    /// Bin the numeric answers to a question into a histogram, optionally weighting respondents
+    pub fn histogram(&self, question: impl Into<QuestionRef>, bins: Option<usize>, weights: Option<&Weights>) -> Result<Histogram> {
+        let question = self.question(question)?;
+        let mut values: Vec<(usize, f64)> = self.numeric_values(question.id)?.into_iter().collect();
+        if values.is_empty() {
+            return Err(SurveyError::EmptyDataset);
+        }
+        values.sort_unstable_by_key(|(respondent_id, _)| *respondent_id);
+
+        let weighted: Vec<(f64, f64)> = values
+            .iter()
+            .map(|(respondent_id, value)| (*value, weights.map_or(1.0, |w| w.get(*respondent_id))))
+            .collect();
+        Ok(Histogram::from_values(question.text.clone(), &weighted, bins))
+    }
+
+    /// Get the numeric answers to a question, keyed by respondent ID
+    pub fn numeric_values(&self, question: impl Into<QuestionRef>) -> Result<HashMap<usize, f64>> {
+        let question_id = self.resolve_question(question)?;
+
+        Ok(self.answers
+            .iter()
+            .filter(|a| a.question_id == question_id)
+            .filter_map(|a| Some((a.respondent_id, a.value.parse::<f64>().ok().filter(|v| v.is_finite())?)))
+            .collect())
+    }
+
+    /// Bootstrap a mean or median of a numeric question, optionally within a subset
+    pub fn bootstrap_numeric(&self, question: impl Into<QuestionRef>, subset: Option<&Subset>, statistic: NumericStatistic, bootstrap: &Bootstrap) -> Result<BootstrapResult> {
+        let values = self.numeric_values(question)?;
+
+        let mut respondent_ids: Vec<usize> = match subset {
+            Some(subset) => subset.respondent_ids.iter().copied().filter(|id| values.contains_key(id)).collect(),
+            None => values.keys().copied().collect(),
+        };
+        respondent_ids.sort_unstable();
+
+        bootstrap.run_on(&respondent_ids, |sample| {
+            let sample_values: Vec<f64> = sample.iter().map(|id| values[id]).collect();
+            statistic.compute(&sample_values)
+        })
+    }
+
+    /// Get the free-text answers to a text question, optionally within a subset
+    pub fn text_answers(&self, question: impl Into<QuestionRef>, subset: Option<&Subset>) -> Result<Vec<&Answer>> {
+        let question = self.question(question)?;
+        let question_id = question.id;
+        if !matches!(question.question_type, QuestionType::Text) {
+            return Err(SurveyError::InvalidQuestionType);
+        }
+
+        let respondents: Option<HashSet<usize>> = subset.map(|s| s.respondent_ids.iter().copied().collect());
+        Ok(self.answers
+            .iter()
+            .filter(|a| a.question_id == question_id)
+            .filter(|a| respondents.as_ref().is_none_or(|ids| ids.contains(&a.respondent_id)))
+            .collect())
+    }
+
+    /// Word and bigram frequencies of a text question, optionally within a subset
+    pub fn text_analysis(&self, question: impl Into<QuestionRef>, subset: Option<&Subset>) -> Result<TextAnalysis> {
+        let question_id = self.resolve_question(question)?;
+        let documents: Vec<&str> = self.text_answers(question_id, subset)?
+            .iter()
+            .map(|a| a.value.as_str())
+            .collect();
+        Ok(TextAnalysis::from_documents(question_id, &self.questions[question_id].text, &documents))
+    }
+
+    /// Terms that distinguish a subset's text answers from everyone's (TF-IDF)
+    pub fn text_keywords(&self, question: impl Into<QuestionRef>, subset: &Subset, top: usize) -> Result<Vec<Keyword>> {
+        let question_id = self.resolve_question(question)?;
+        let all: Vec<&str> = self.text_answers(question_id, None)?.iter().map(|a| a.value.as_str()).collect();
+        let within: Vec<&str> = self.text_answers(question_id, Some(subset))?.iter().map(|a| a.value.as_str()).collect();
+        Ok(text::tfidf_keywords(&within, &all, top))
+    }
+
+    /// Find a keyword in text answers with `width` characters of context on each side
+    pub fn keyword_in_context(&self, question: impl Into<QuestionRef>, keyword: &str, width: usize, subset: Option<&Subset>) -> Result<Vec<KeywordInContext>> {
+        Ok(self.text_answers(question, subset)?
+            .iter()
+            .flat_map(|a| text::keyword_in_context(a.respondent_id, &a.value, keyword, width))
+            .collect())
+    }
+
+    /// Get answer distributions for every choice question in a group
+    pub fn group_distributions(&self, name: &str) -> Result<Vec<AnswerDistribution>> {
+        let group = self.find_group(name)
+            .ok_or_else(|| SurveyError::GroupNotFound(name.to_string()))?;
+
+        let mut distributions = Vec::new();
+        for &question_id in &group.question_ids {
+            match self.get_distribution(question_id) {
+                Ok(distribution) => distributions.push(distribution),
+                Err(SurveyError::InvalidQuestionType) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(distributions)
+    }
+
+    /// Create a subset of respondents who chose an option in any question of a group
+    pub fn create_group_subset(&self, name: &str, option: &str) -> Result<Subset> {
+        let group = self.find_group(name)
+            .ok_or_else(|| SurveyError::GroupNotFound(name.to_string()))?;
+
+        let mut respondent_ids: Vec<usize> = self.answers
+            .iter()
+            .filter(|a| group.contains(a.question_id))
+            .filter(|a| split_options(&a.value).contains(&option))
+            .map(|a| a.respondent_id)
+            .collect();
+        respondent_ids.sort_unstable();
+        respondent_ids.dedup();
+
+        Ok(Subset {
+            question_id: group.question_ids[0],
+            group: Some(group.name.clone()),
+            option: option.to_string(),
+            respondent_ids,
+            total_respondents: self.respondent_count,
+        })
+    }
+
+    /// Find all `*HaveWorkedWith` / `*WantToWorkWith` question pairs as (family, have_id, want_id)
+    pub fn worked_with_pairs(&self) -> Vec<(String, usize, usize)> {
+        self.questions
+            .iter()
+            .filter_map(|have| {
+                let family = have.text.strip_suffix(HAVE_WORKED_WITH)?;
+                let want_text = format!("{}{}", family, WANT_TO_WORK_WITH);
+                let want = self.questions.iter().find(|q| q.text == want_text)?;
+                Some((family.to_string(), have.id, want.id))
+            })
+            .collect()
+    }
+
+    /// Compute admired/desired statistics for every paired question family
+    pub fn admired_desired_all(&self) -> Result<Vec<AdmiredDesired>> {
+        self.worked_with_pairs()
+            .into_iter()
+            .map(|(_, have_id, want_id)| self.admired_desired(have_id, want_id))
+            .collect()
+    }
+
+    /// Compute admired/desired statistics and migration flows for a question pair
+    pub fn admired_desired(&self, have_question: impl Into<QuestionRef>, want_question: impl Into<QuestionRef>) -> Result<AdmiredDesired> {
+        let have_question = self.question(have_question)?;
+        let want_question = self.question(want_question)?;
+        let (have_question_id, want_question_id) = (have_question.id, want_question.id);
+
+        let have = self.selections_by_respondent(have_question_id);
+        let want = self.selections_by_respondent(want_question_id);
+
+        let mut worked_with: HashMap<&str, usize> = HashMap::new();
+        let mut want_to_work_with: HashMap<&str, usize> = HashMap::new();
+        let mut continuing: HashMap<&str, usize> = HashMap::new();
+        let mut migrations: HashMap<(&str, &str), usize> = HashMap::new();
+        let empty = BTreeSet::new();
+
+        for selected in have.values() {
+            for option in selected {
+                *worked_with.entry(option).or_insert(0) += 1;
+            }
+        }
+
+        for (respondent_id, wanted) in &want {
+            let had = have.get(respondent_id).unwrap_or(&empty);
+            for option in wanted {
+                *want_to_work_with.entry(option).or_insert(0) += 1;
+                if had.contains(option) {
+                    *continuing.entry(option).or_insert(0) += 1;
+                } else {
+                    for from in had {
+                        *migrations.entry((from, option)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let all_options: BTreeSet<&str> = worked_with.keys().chain(want_to_work_with.keys()).copied().collect();
+        let mut options: Vec<AdmiredDesiredOption> = all_options
+            .into_iter()
+            .map(|option| {
+                let worked = worked_with.get(option).copied().unwrap_or(0);
+                let wanted = want_to_work_with.get(option).copied().unwrap_or(0);
+                let kept = continuing.get(option).copied().unwrap_or(0);
+                AdmiredDesiredOption {
+                    option: option.to_string(),
+                    worked_with: worked,
+                    want_to_work_with: wanted,
+                    continuing: kept,
+                    admired: if worked > 0 { (kept as f64 / worked as f64) * 100.0 } else { 0.0 },
+                    desired: if self.respondent_count > 0 {
+                        (wanted as f64 / self.respondent_count as f64) * 100.0
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+        options.sort_by(|a, b| b.admired.total_cmp(&a.admired).then_with(|| a.option.cmp(&b.option)));
+
+        let mut migrations: Vec<Migration> = migrations
+            .into_iter()
+            .map(|((from, to), count)| Migration { from: from.to_string(), to: to.to_string(), count })
+            .collect();
+        migrations.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to))));
+
+        let family = have_question.text.strip_suffix(HAVE_WORKED_WITH)
+            .or_else(|| want_question.text.strip_suffix(WANT_TO_WORK_WITH))
+            .unwrap_or(&have_question.text)
+            .to_string();
+
+        Ok(AdmiredDesired {
+            family,
+            have_question_id,
+            want_question_id,
+            options,
+            migrations,
+            total_respondents: self.respondent_count,
+        })
+    }
+
+    /// Collect the chosen options of every respondent, splitting only multiple choice answers
+    fn choices_by_respondent(&self, question: &Question) -> HashMap<usize, Vec<&str>> {
+        if matches!(question.question_type, QuestionType::MultipleChoice) {
+            return self.selections_by_respondent(question.id)
+                .into_iter()
+                .map(|(respondent_id, options)| (respondent_id, options.into_iter().collect()))
+                .collect();
+        }
+
+        self.answers
+            .iter()
+            .filter(|a| a.question_id == question.id)
+            .map(|a| (a.respondent_id, vec![a.value.as_str()]))
+            .collect()
+    }
+
+    /// Collect the selected options of every respondent who answered a question
+    fn selections_by_respondent(&self, question_id: usize) -> HashMap<usize, BTreeSet<&str>> {
+        let mut selections: HashMap<usize, BTreeSet<&str>> = HashMap::new();
+        for answer in self.answers.iter().filter(|a| a.question_id == question_id) {
+            selections
+                .entry(answer.respondent_id)
+                .or_default()
+                .extend(split_options(&answer.value));
+        }
+        selections
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::weights::RakeTarget;
 
     #[test]
     fn test_question_type_inference() {
@@ -276,6 +1136,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_search_functionality() {
         let questions = vec![
             Question {
@@ -295,6 +1156,7 @@ mod tests {
         let survey = Survey {
             questions,
             answers: vec![],
+            groups: vec![],
             respondent_count: 0,
         };
 
@@ -306,4 +1168,361 @@ mod tests {
         assert_eq!(option_results.len(), 1);
         assert_eq!(option_results[0], (0, "Rust"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_search_modes() {
+        let survey = Survey {
+            questions: vec![Question {
+                id: 0,
+                text: "What programming language do you use?".to_string(),
+                question_type: QuestionType::SingleChoice,
+                options: vec!["Rust".to_string(), "Python".to_string()],
+            }],
+            answers: vec![],
+            groups: vec![],
+            respondent_count: 0,
+        };
+
+        let fuzzy = survey.search_questions_with("pgmlang", SearchMode::Fuzzy).unwrap();
+        assert_eq!(fuzzy.len(), 1);
+        assert_eq!(fuzzy[0].question_id, 0);
+
+        let regex = survey.search_options_with("^(rust|go)$", SearchMode::Regex).unwrap();
+        assert_eq!(regex.len(), 1);
+        assert_eq!(regex[0].option.as_deref(), Some("Rust"));
+        assert!(survey.search_options_with("[", SearchMode::Regex).is_err());
+    }
+
+    #[test]
+    fn test_admired_desired() {
+        let question = |id: usize, text: &str| Question {
+            id,
+            text: text.to_string(),
+            question_type: QuestionType::MultipleChoice,
+            options: vec![],
+        };
+        let answer = |respondent_id: usize, question_id: usize, value: &str| Answer {
+            respondent_id,
+            question_id,
+            value: value.to_string(),
+        };
+
+        let survey = Survey {
+            questions: vec![
+                question(0, "LanguageHaveWorkedWith"),
+                question(1, "LanguageWantToWorkWith"),
+                question(2, "Country"),
+            ],
+            answers: vec![
+                answer(0, 0, "Rust;Python"),
+                answer(0, 1, "Rust"),
+                answer(1, 0, "Python"),
+                answer(1, 1, "Rust;Go"),
+                answer(2, 0, "Rust"),
+                answer(3, 1, "Python"),
+            ],
+            groups: vec![],
+            respondent_count: 4,
+        };
+
+        assert_eq!(survey.worked_with_pairs(), vec![("Language".to_string(), 0, 1)]);
+
+        let result = survey.admired_desired(0, 1).unwrap();
+        assert_eq!(result.family, "Language");
+
+        let rust = result.option("Rust").unwrap();
+        assert_eq!((rust.worked_with, rust.continuing, rust.want_to_work_with), (2, 1, 2));
+        assert_eq!(rust.admired, 50.0);
+        assert_eq!(rust.desired, 50.0);
+
+        let python = result.option("Python").unwrap();
+        assert_eq!(python.admired, 0.0);
+        assert_eq!(python.desired, 25.0);
+
+        let flows: Vec<_> = result.migrations.iter().map(|m| (m.from.as_str(), m.to.as_str(), m.count)).collect();
+        assert_eq!(flows, vec![("Python", "Go", 1), ("Python", "Rust", 1)]);
+
+        // Questions can be referred to by column name as well as position
+        let by_name = survey.admired_desired("languagehaveworkedwith", "LanguageWantToWorkWith").unwrap();
+        assert_eq!(by_name.option("Rust").unwrap().admired, 50.0);
+    }
+
+    #[test]
+    fn test_question_refs() {
+        let question = |id: usize, text: &str| Question {
+            id,
+            text: text.to_string(),
+            question_type: QuestionType::SingleChoice,
+            options: vec![],
+        };
+        let survey = Survey {
+            questions: vec![
+                question(0, "LanguageHaveWorkedWith"),
+                question(1, "LanguageWantToWorkWith"),
+                question(2, "Country"),
+            ],
+            answers: vec![],
+            groups: vec![],
+            respondent_count: 0,
+        };
+
+        assert_eq!("2".parse::<QuestionRef>().unwrap(), QuestionRef::Index(2));
+        assert_eq!("Country".parse::<QuestionRef>().unwrap(), QuestionRef::from("Country"));
+        assert_eq!(survey.resolve_question("country").unwrap(), 2);
+        assert_eq!(survey.resolve_question(1).unwrap(), 1);
+        assert_eq!(survey.resolve_question("1").unwrap(), 1);
+        assert!(matches!(survey.resolve_question(7), Err(SurveyError::QuestionNotFound(7))));
+
+        match survey.resolve_question("LanguageHaveWorkdWith") {
+            Err(SurveyError::QuestionNameNotFound { name, suggestions }) => {
+                assert_eq!(name, "LanguageHaveWorkdWith");
+                assert_eq!(suggestions, vec!["LanguageHaveWorkedWith", "LanguageWantToWorkWith"]);
+            }
+            other => panic!("expected QuestionNameNotFound, got {:?}", other),
+        }
+        let error = survey.resolve_question("Countri").unwrap_err().to_string();
+        assert_eq!(error, "Question not found: Countri (did you mean Country?)");
+    }
+
+    #[test]
+    fn test_question_ref_serde_round_trip() {
+        let parse = |json: &str| serde_json::from_str::<QuestionRef>(json).unwrap();
+        assert_eq!(parse("5"), QuestionRef::Index(5));
+        // Digit strings are positions, as on the command line
+        assert_eq!(parse("\"5\""), QuestionRef::Index(5));
+        assert_eq!(parse("\" RemoteWork \""), QuestionRef::Name("RemoteWork".to_string()));
+        assert!(serde_json::from_str::<QuestionRef>("-1").is_err());
+
+        for question in [QuestionRef::Index(12), QuestionRef::from("LanguageHaveWorkedWith")] {
+            let json = serde_json::to_string(&question).unwrap();
+            assert_eq!(serde_json::from_str::<QuestionRef>(&json).unwrap(), question);
+        }
+        assert_eq!(serde_json::to_string(&QuestionRef::Index(12)).unwrap(), "12");
+    }
+
+    #[test]
+    fn test_question_grouping() {
+        let questions: Vec<Question> = ["LanguageHaveWorkedWith", "LanguageWantToWorkWith", "LanguageAdmired", "Country", "Knowledge_1", "Knowledge_2", "Frequency_1"]
+            .iter()
+            .enumerate()
+            .map(|(id, text)| Question {
+                id,
+                text: text.to_string(),
+                question_type: QuestionType::MultipleChoice,
+                options: vec![],
+            })
+            .collect();
+
+        let mut survey = Survey {
+            groups: group_questions(&questions, family_name),
+            questions,
+            answers: vec![
+                Answer { respondent_id: 0, question_id: 0, value: "Rust;Go".to_string() },
+                Answer { respondent_id: 1, question_id: 1, value: "Rust".to_string() },
+                Answer { respondent_id: 2, question_id: 0, value: "Python".to_string() },
+            ],
+            respondent_count: 3,
+        };
+
+        let names: Vec<_> = survey.question_groups().iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["Language", "Knowledge"]);
+        assert_eq!(survey.find_group("language").unwrap().question_ids, vec![0, 1, 2]);
+
+        let subset = survey.create_group_subset("Language", "Rust").unwrap();
+        assert_eq!(subset.respondent_ids, vec![0, 1]);
+        assert_eq!(subset.group.as_deref(), Some("Language"));
+        assert!(subset.display().starts_with("Subset for Group 'Language' - Option 'Rust'"));
+        let json = serde_json::to_value(&subset).unwrap();
+        assert_eq!(json["group"], "Language");
+        assert!(matches!(survey.create_group_subset("Platform", "AWS"), Err(SurveyError::GroupNotFound(_))));
+
+        survey.regroup(|column| column.starts_with('K').then(|| "Skills".to_string()));
+        assert_eq!(survey.question_groups().len(), 1);
+        assert_eq!(survey.question_groups()[0].question_ids, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_weighted_analysis() {
+        let question = |id: usize, text: &str, question_type: QuestionType| Question {
+            id,
+            text: text.to_string(),
+            question_type,
+            options: vec![],
+        };
+        let answer = |respondent_id: usize, question_id: usize, value: &str| Answer {
+            respondent_id,
+            question_id,
+            value: value.to_string(),
+        };
+
+        let survey = Survey {
+            questions: vec![
+                question(0, "Country", QuestionType::SingleChoice),
+                question(1, "RemoteWork", QuestionType::SingleChoice),
+                question(2, "YearsCode", QuestionType::Numeric),
+            ],
+            answers: vec![
+                answer(0, 0, "Germany"),
+                answer(1, 0, "Germany"),
+                answer(2, 0, "Germany"),
+                answer(3, 0, "Poland"),
+                answer(0, 1, "Remote"),
+                answer(1, 1, "Remote"),
+                answer(2, 1, "Hybrid"),
+                answer(3, 1, "Hybrid"),
+                answer(0, 2, "1"),
+                answer(1, 2, "2"),
+                answer(2, 2, "3"),
+                answer(3, 2, "10"),
+            ],
+            groups: vec![],
+            respondent_count: 4,
+        };
+
+        // Rake to a 50/50 country split: Germans get 2/3, the Pole gets 2
+        let targets = vec![RakeTarget {
+            question: QuestionRef::Index(0),
+            marginals: HashMap::from([("Germany".to_string(), 50.0), ("Poland".to_string(), 50.0)]),
+        }];
+        let weights = Weights::rake(&survey, &targets, 50).unwrap();
+        assert!((weights.get(0) - 2.0 / 3.0).abs() < 1e-9);
+        assert!((weights.get(3) - 2.0).abs() < 1e-9);
+
+        let country = survey.get_weighted_distribution(0, &weights).unwrap();
+        assert!((country.distribution["Poland"].1 - 50.0).abs() < 1e-9);
+        assert!((country.effective_sample_size - 16.0 / 5.333_333_333_333_333).abs() < 1e-9);
+
+        let remote = survey.get_weighted_distribution(1, &weights).unwrap();
+        assert!((remote.distribution["Hybrid"].1 - (2.0 / 3.0 + 2.0) / 4.0 * 100.0).abs() < 1e-9);
+
+        let crosstab = survey.cross_tab(0, 1, None).unwrap();
+        assert_eq!(crosstab.row_options, vec!["Germany", "Poland"]);
+        assert_eq!(crosstab.column_options, vec!["Hybrid", "Remote"]);
+        assert_eq!(crosstab.counts, vec![vec![1.0, 2.0], vec![1.0, 0.0]]);
+        let weighted_crosstab = survey.cross_tab(0, 1, Some(&weights)).unwrap();
+        assert!((weighted_crosstab.counts[1][0] - 2.0).abs() < 1e-9);
+
+        let summary = survey.numeric_summary(2, None).unwrap();
+        assert_eq!((summary.count, summary.mean, summary.median), (4, 4.0, 2.5));
+        let weighted_summary = survey.numeric_summary(2, Some(&weights)).unwrap();
+        assert!((weighted_summary.mean - (6.0 * 2.0 / 3.0 + 20.0) / 4.0).abs() < 1e-9);
+        assert_eq!(weighted_summary.median, 6.5);
+    }
+
+    #[test]
+    fn test_compare_subsets() {
+        let mut answers = Vec::new();
+        for respondent_id in 0..200 {
+            let work = if respondent_id < 100 { "Remote" } else { "In-person" };
+            // 60% of remote workers and 30% of in-person workers use Rust
+            let uses_rust = if respondent_id < 100 { respondent_id % 10 < 6 } else { respondent_id % 10 < 3 };
+            let language = if uses_rust { "Rust" } else { "Python" };
+            answers.push(Answer { respondent_id, question_id: 0, value: work.to_string() });
+            answers.push(Answer { respondent_id, question_id: 1, value: language.to_string() });
+        }
+
+        let survey = Survey {
+            questions: vec![
+                Question { id: 0, text: "RemoteWork".to_string(), question_type: QuestionType::SingleChoice, options: vec![] },
+                Question { id: 1, text: "Language".to_string(), question_type: QuestionType::SingleChoice, options: vec![] },
+            ],
+            answers,
+            groups: vec![],
+            respondent_count: 200,
+        };
+
+        let remote = survey.create_subset(0, "Remote").unwrap();
+        let in_person = survey.create_subset(0, "In-person").unwrap();
+        assert_eq!(survey.get_subset_distribution(1, &remote).unwrap().distribution["Rust"], (60, 60.0));
+
+        let comparison = survey.compare(1, &remote, &in_person).unwrap();
+        assert_eq!((comparison.left_total, comparison.right_total), (100, 100));
+        let rust = comparison.options.iter().find(|o| o.option == "Rust").unwrap();
+        assert!((rust.difference - 30.0).abs() < 1e-9);
+        assert!((rust.z_score - 4.2640).abs() < 1e-3);
+        assert!(rust.significant);
+        assert!(rust.adjusted_p_value >= rust.p_value);
+
+        assert!(survey.compare_with(1, &remote, &in_person, Correction::None, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_text_answers() {
+        let survey = Survey {
+            questions: vec![
+                Question { id: 0, text: "RemoteWork".to_string(), question_type: QuestionType::SingleChoice, options: vec![] },
+                Question { id: 1, text: "Please describe your setup".to_string(), question_type: QuestionType::Text, options: vec![] },
+            ],
+            answers: vec![
+                Answer { respondent_id: 0, question_id: 0, value: "Remote".to_string() },
+                Answer { respondent_id: 0, question_id: 1, value: "Two monitors and a standing desk".to_string() },
+                Answer { respondent_id: 1, question_id: 0, value: "Hybrid".to_string() },
+                Answer { respondent_id: 1, question_id: 1, value: "Laptop at the office desk".to_string() },
+            ],
+            groups: vec![],
+            respondent_count: 2,
+        };
+
+        assert!(matches!(survey.text_analysis(0, None), Err(SurveyError::InvalidQuestionType)));
+        assert_eq!(survey.text_analysis(1, None).unwrap().words[0], ("desk".to_string(), 2));
+
+        let remote = survey.create_subset(0, "Remote").unwrap();
+        let keywords = survey.text_keywords(1, &remote, 4).unwrap();
+        assert!(keywords.iter().all(|k| ["two", "monitors", "standing", "desk"].contains(&k.term.as_str())));
+        assert_eq!(keywords.last().unwrap().term, "desk");
+
+        let hits = survey.keyword_in_context(1, "desk", 10, None).unwrap();
+        assert_eq!(hits.iter().map(|h| h.respondent_id).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_export_rows() {
+        let survey = Survey {
+            questions: vec![
+                Question { id: 0, text: "ResponseId".to_string(), question_type: QuestionType::SingleChoice, options: vec![] },
+                Question { id: 1, text: "RemoteWork".to_string(), question_type: QuestionType::SingleChoice, options: vec![] },
+                Question { id: 2, text: "LanguageHaveWorkedWith".to_string(), question_type: QuestionType::MultipleChoice, options: vec![] },
+            ],
+            answers: vec![
+                Answer { respondent_id: 0, question_id: 0, value: "1".to_string() },
+                Answer { respondent_id: 0, question_id: 1, value: "Remote".to_string() },
+                Answer { respondent_id: 0, question_id: 2, value: "Rust;Go".to_string() },
+                Answer { respondent_id: 1, question_id: 0, value: "2".to_string() },
+                Answer { respondent_id: 1, question_id: 1, value: "Hybrid".to_string() },
+                Answer { respondent_id: 2, question_id: 0, value: "003".to_string() },
+                Answer { respondent_id: 2, question_id: 1, value: "Remote".to_string() },
+            ],
+            groups: vec![],
+            respondent_count: 3,
+        };
+        let remote = survey.create_subset(1, "Remote").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let csv_path = dir.path().join("remote.csv");
+        let columns = [QuestionRef::from("ResponseId"), QuestionRef::from("LanguageHaveWorkedWith")];
+        assert_eq!(survey.export_rows(&remote, &columns, &csv_path).unwrap(), 2);
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), "ResponseId,LanguageHaveWorkedWith\n1,Rust;Go\n003,\n");
+
+        // Repeated columns are written once, in first-mention order
+        let columns = [QuestionRef::from("RemoteWork"), QuestionRef::Index(0), QuestionRef::from("RemoteWork")];
+        survey.export_rows(&remote, &columns, &csv_path).unwrap();
+        assert_eq!(std::fs::read_to_string(&csv_path).unwrap(), "RemoteWork,ResponseId\nRemote,1\nRemote,003\n");
+
+        // An xlsx export reads back as a survey of just those respondents
+        let xlsx_path = dir.path().join("remote.xlsx");
+        survey.export_rows(&remote, &[], &xlsx_path).unwrap();
+        let exported = Survey::from_excel(&xlsx_path).unwrap();
+        assert_eq!(exported.respondent_count(), 2);
+        assert_eq!(exported.get_distribution("RemoteWork").unwrap().distribution["Remote"], (2, 100.0));
+        // Cells are written verbatim, so leading zeros survive
+        assert_eq!(exported.get_distribution("ResponseId").unwrap().distribution["003"].0, 1);
+        let from_bytes = Survey::from_excel_bytes(&std::fs::read(&xlsx_path).unwrap()).unwrap();
+        assert_eq!(from_bytes.respondent_count(), 2);
+        assert!(Survey::from_excel_bytes(b"not a workbook").is_err());
+
+        assert!(matches!(
+            survey.export_rows(&remote, &[], dir.path().join("remote.txt")),
+            Err(SurveyError::InvalidParameter(_))
+        ));
+    }
+}
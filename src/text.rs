@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+/// Common English words ignored in frequency and keyword analysis
+pub const STOP_WORDS: &[&str] = &[
+    "a", "about", "above", "after", "again", "against", "all", "am", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "being", "below", "between", "both", "but", "by", "can", "could",
+    "did", "do", "does", "doing", "don", "down", "during", "each", "etc", "few", "for", "from", "further",
+    "had", "has", "have", "having", "he", "her", "here", "hers", "herself", "him", "himself", "his", "how",
+    "i", "if", "in", "into", "is", "it", "its", "itself", "just", "me", "more", "most", "my", "myself", "no",
+    "nor", "not", "now", "of", "off", "on", "once", "only", "or", "other", "our", "ours", "ourselves", "out",
+    "over", "own", "really", "same", "she", "should", "so", "some", "such", "than", "that", "the", "their",
+    "theirs", "them", "themselves", "then", "there", "these", "they", "this", "those", "through", "to",
+    "too", "under", "until", "up", "use", "used", "using", "very", "was", "we", "were", "what", "when",
+    "where", "which", "while", "who", "whom", "why", "will", "with", "would", "you", "your", "yours",
+    "yourself", "yourselves",
+];
+
+/// Check if a (lowercase) token is a stop word
+pub fn is_stop_word(token: &str) -> bool {
+    STOP_WORDS.binary_search(&token).is_ok()
+}
+
+/// Split text into lowercase word tokens, keeping `+` and `#` so `C++` and `C#` survive
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#' || c == '\''))
+        .map(|token| token.trim_matches('\'').to_lowercase())
+        .filter(|token| !token.is_empty() && token.chars().any(char::is_alphanumeric))
+        .collect()
+}
+
+/// Check if a token is worth counting: not a stop word and not a bare number
+fn is_content_token(token: &str) -> bool {
+    !is_stop_word(token) && !token.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Tokens worth counting: no stop words and no bare numbers
+pub fn content_tokens(text: &str) -> Vec<String> {
+    tokenize(text).into_iter().filter(|token| is_content_token(token)).collect()
+}
+
+/// Adjacent token pairs where neither token is a stop word or a bare number
+pub fn bigrams(text: &str) -> Vec<String> {
+    tokenize(text)
+        .windows(2)
+        .filter(|pair| pair.iter().all(|token| is_content_token(token)))
+        .map(|pair| format!("{} {}", pair[0], pair[1]))
+        .collect()
+}
+
+/// Word and bigram frequencies of a question's free-text answers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextAnalysis {
+    pub question_id: usize,
+    pub question_text: String,
+    pub documents: usize,
+    pub words: Vec<(String, usize)>,   // sorted by count (descending)
+    pub bigrams: Vec<(String, usize)>, // sorted by count (descending)
+}
+
+impl TextAnalysis {
+    /// Count words and bigrams across a set of answers
+    pub fn from_documents(question_id: usize, question_text: &str, documents: &[&str]) -> Self {
+        let mut words = HashMap::new();
+        let mut pairs = HashMap::new();
+        for document in documents {
+            for token in content_tokens(document) {
+                *words.entry(token).or_insert(0) += 1;
+            }
+            for bigram in bigrams(document) {
+                *pairs.entry(bigram).or_insert(0) += 1;
+            }
+        }
+
+        Self {
+            question_id,
+            question_text: question_text.to_string(),
+            documents: documents.len(),
+            words: sorted_counts(words),
+            bigrams: sorted_counts(pairs),
+        }
+    }
+
+    /// Display the N most frequent words and bigrams
+    pub fn display(&self, top: usize) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Question {}: {}\n", self.question_id, self.question_text));
+        output.push_str(&format!("Text Responses: {}\n", self.documents));
+
+        output.push_str("Top words:\n");
+        for (word, count) in self.words.iter().take(top) {
+            output.push_str(&format!("  {}: {}\n", word, count));
+        }
+
+        output.push_str("Top bigrams:\n");
+        for (bigram, count) in self.bigrams.iter().take(top) {
+            output.push_str(&format!("  {}: {}\n", bigram, count));
+        }
+
+        output
+    }
+}
+
+/// A term that characterizes a subset's answers relative to all answers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyword {
+    pub term: String,
+    pub count: usize,     // occurrences in the subset's answers
+    pub documents: usize, // answers (across all respondents) containing the term
+    pub tf_idf: f64,
+}
+
+/// Rank the terms of `subset_documents` by TF-IDF, using `all_documents` for document frequencies
+pub fn tfidf_keywords(subset_documents: &[&str], all_documents: &[&str], top: usize) -> Vec<Keyword> {
+    let mut document_frequency: HashMap<String, usize> = HashMap::new();
+    for document in all_documents {
+        let terms: HashSet<String> = content_tokens(document).into_iter().collect();
+        for term in terms {
+            *document_frequency.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    let mut term_counts: HashMap<String, usize> = HashMap::new();
+    let mut total_terms = 0;
+    for document in subset_documents {
+        for term in content_tokens(document) {
+            *term_counts.entry(term).or_insert(0) += 1;
+            total_terms += 1;
+        }
+    }
+
+    let n = all_documents.len() as f64;
+    let mut keywords: Vec<Keyword> = term_counts
+        .into_iter()
+        .map(|(term, count)| {
+            let documents = document_frequency.get(&term).copied().unwrap_or(0);
+            let idf = ((1.0 + n) / (1.0 + documents as f64)).ln() + 1.0;
+            let tf = count as f64 / total_terms as f64;
+            Keyword { term, count, documents, tf_idf: tf * idf }
+        })
+        .collect();
+
+    keywords.sort_by(|a, b| b.tf_idf.total_cmp(&a.tf_idf).then_with(|| a.term.cmp(&b.term)));
+    keywords.truncate(top);
+    keywords
+}
+
+/// One occurrence of a keyword with the text around it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeywordInContext {
+    pub respondent_id: usize,
+    pub left: String,
+    pub keyword: String,
+    pub right: String,
+}
+
+impl KeywordInContext {
+    /// Display the occurrence as a single aligned line
+    pub fn display(&self, width: usize) -> String {
+        format!(
+            "{:>6}  {:>width$} [{}] {}",
+            self.respondent_id,
+            self.left,
+            self.keyword,
+            self.right,
+            width = width
+        )
+    }
+}
+
+/// Find whole-word, case-insensitive occurrences of `keyword`, keeping `width` characters of context
+pub fn keyword_in_context(respondent_id: usize, text: &str, keyword: &str, width: usize) -> Vec<KeywordInContext> {
+    let needle: Vec<char> = keyword.to_lowercase().chars().collect();
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let is_word = |c: &char| c.is_alphanumeric();
+
+    let mut matches = Vec::new();
+    if needle.is_empty() || needle.len() > lower.len() {
+        return matches;
+    }
+
+    for start in 0..=(lower.len() - needle.len()) {
+        let end = start + needle.len();
+        if lower[start..end] != needle[..] {
+            continue;
+        }
+        if (start > 0 && is_word(&chars[start - 1])) || chars.get(end).is_some_and(is_word) {
+            continue;
+        }
+
+        let left: String = chars[start.saturating_sub(width)..start].iter().collect();
+        let right: String = chars[end..(end + width).min(chars.len())].iter().collect();
+        matches.push(KeywordInContext {
+            respondent_id,
+            left: left.replace('\n', " "),
+            keyword: chars[start..end].iter().collect(),
+            right: right.replace('\n', " "),
+        });
+    }
+
+    matches
+}
+
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut sorted: Vec<_> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenization() {
+        assert!(STOP_WORDS.windows(2).all(|pair| pair[0] < pair[1]), "STOP_WORDS must stay sorted");
+        assert_eq!(tokenize("I use C++ and C#, daily!"), vec!["i", "use", "c++", "and", "c#", "daily"]);
+        assert_eq!(content_tokens("I use Rust for 10 years"), vec!["rust", "years"]);
+        assert_eq!(bigrams("Rust analyzer is a great language server"), vec!["rust analyzer", "great language", "language server"]);
+        // Bare numbers are dropped from bigrams as from single words
+        assert_eq!(bigrams("Rust 2021 edition for embedded systems"), vec!["embedded systems"]);
+    }
+
+    #[test]
+    fn test_frequencies_and_keywords() {
+        let documents = ["Rust is great", "rust and docker", "Docker compose", "vim"];
+        let analysis = TextAnalysis::from_documents(3, "Describe your setup", &documents);
+        assert_eq!(analysis.words[0], ("docker".to_string(), 2));
+        assert_eq!(analysis.words[1], ("rust".to_string(), 2));
+        assert_eq!(analysis.bigrams, vec![("docker compose".to_string(), 1)]);
+
+        let keywords = tfidf_keywords(&documents[3..], &documents, 5);
+        assert_eq!(keywords[0].term, "vim");
+        assert_eq!(keywords[0].documents, 1);
+    }
+
+    #[test]
+    fn test_keyword_in_context() {
+        let hits = keyword_in_context(7, "Rust and rusty tools; I love RUST", "rust", 5);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].left.as_str(), hits[0].keyword.as_str(), hits[0].right.as_str()), ("", "Rust", " and "));
+        assert_eq!((hits[1].left.as_str(), hits[1].keyword.as_str()), ("love ", "RUST"));
+    }
+}
@@ -0,0 +1,178 @@
+use serde_json::{json, Value};
+use crate::analysis::{AnswerDistribution, CrossTab, WeightedDistribution};
+use crate::chart::Histogram;
+
+/// Vega-Lite schema the generated specs target
+const VEGA_LITE_SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// Results that can be described as a Vega-Lite chart with inline data
+pub trait ToVegaLite {
+    /// Build a complete Vega-Lite spec
+    fn to_vega_lite(&self) -> Value;
+}
+
+impl ToVegaLite for AnswerDistribution {
+    fn to_vega_lite(&self) -> Value {
+        let mut values: Vec<(&String, usize, f64)> = self
+            .distribution
+            .iter()
+            .map(|(option, (count, percentage))| (option, *count, *percentage))
+            .collect();
+        values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let values: Vec<Value> = values
+            .into_iter()
+            .map(|(option, count, percentage)| json!({"option": option, "count": count, "percentage": percentage}))
+            .collect();
+
+        share_bars(&self.question_text, values, "count")
+    }
+}
+
+impl ToVegaLite for WeightedDistribution {
+    fn to_vega_lite(&self) -> Value {
+        let mut values: Vec<(&String, f64, f64)> = self
+            .distribution
+            .iter()
+            .map(|(option, (weight, percentage))| (option, *weight, *percentage))
+            .collect();
+        values.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let values: Vec<Value> = values
+            .into_iter()
+            .map(|(option, weight, percentage)| json!({"option": option, "weight": weight, "percentage": percentage}))
+            .collect();
+
+        share_bars(&self.question_text, values, "weight")
+    }
+}
+
+impl ToVegaLite for CrossTab {
+    /// Long-format cells stacked to 100% per row option
+    fn to_vega_lite(&self) -> Value {
+        let percentages = self.row_percentages();
+        let mut values = Vec::new();
+        for (r, row) in self.row_options.iter().enumerate() {
+            for (c, column) in self.column_options.iter().enumerate() {
+                values.push(json!({
+                    "row": row,
+                    "column": column,
+                    "count": self.counts[r][c],
+                    "percentage": percentages[r][c],
+                }));
+            }
+        }
+
+        json!({
+            "$schema": VEGA_LITE_SCHEMA,
+            "title": format!("{} by {}", self.row_question_text, self.column_question_text),
+            "data": {"values": values},
+            "mark": "bar",
+            "encoding": {
+                "y": {"field": "row", "type": "nominal", "sort": self.row_options, "title": self.row_question_text},
+                "x": {"field": "percentage", "type": "quantitative", "stack": "normalize", "title": "Share of row"},
+                "color": {"field": "column", "type": "nominal", "sort": self.column_options, "title": self.column_question_text},
+                "tooltip": [
+                    {"field": "row", "type": "nominal"},
+                    {"field": "column", "type": "nominal"},
+                    {"field": "count", "type": "quantitative", "format": ".1f"},
+                    {"field": "percentage", "type": "quantitative", "format": ".1f"},
+                ],
+            },
+        })
+    }
+}
+
+impl ToVegaLite for Histogram {
+    /// Pre-binned counts, so viewers show exactly the bins computed here
+    fn to_vega_lite(&self) -> Value {
+        let values: Vec<Value> = self
+            .bins
+            .iter()
+            .map(|bin| json!({"lower": bin.lower, "upper": bin.upper, "count": bin.count}))
+            .collect();
+
+        json!({
+            "$schema": VEGA_LITE_SCHEMA,
+            "title": self.title,
+            "data": {"values": values},
+            "mark": "bar",
+            "encoding": {
+                "x": {"field": "lower", "type": "quantitative", "bin": {"binned": true}, "title": self.title},
+                "x2": {"field": "upper"},
+                "y": {"field": "count", "type": "quantitative", "title": "Count"},
+                "tooltip": [
+                    {"field": "lower", "type": "quantitative"},
+                    {"field": "upper", "type": "quantitative"},
+                    {"field": "count", "type": "quantitative", "format": ".1f"},
+                ],
+            },
+        })
+    }
+}
+
+/// Horizontal bars of each option's share, largest first
+fn share_bars(title: &str, values: Vec<Value>, count_field: &str) -> Value {
+    json!({
+        "$schema": VEGA_LITE_SCHEMA,
+        "title": title,
+        "data": {"values": values},
+        "mark": "bar",
+        "encoding": {
+            "y": {"field": "option", "type": "nominal", "sort": "-x", "title": null},
+            "x": {"field": "percentage", "type": "quantitative", "title": "Share (%)"},
+            "tooltip": [
+                {"field": "option", "type": "nominal"},
+                {"field": count_field, "type": "quantitative"},
+                {"field": "percentage", "type": "quantitative", "format": ".1f"},
+            ],
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::survey::QuestionType;
+
+    #[test]
+    fn test_vega_lite_specs() {
+        let distribution = AnswerDistribution {
+            question_id: 2,
+            question_text: "RemoteWork".to_string(),
+            question_type: QuestionType::SingleChoice,
+            distribution: HashMap::from([
+                ("Remote".to_string(), (6, 60.0)),
+                ("In-person".to_string(), (4, 40.0)),
+            ]),
+            total_responses: 10,
+            intervals: HashMap::new(),
+        };
+        let spec = distribution.to_vega_lite();
+        assert_eq!(spec["$schema"], VEGA_LITE_SCHEMA);
+        assert_eq!(spec["title"], "RemoteWork");
+        assert_eq!(spec["data"]["values"][0], json!({"option": "Remote", "count": 6, "percentage": 60.0}));
+        assert_eq!(spec["encoding"]["x"]["field"], "percentage");
+
+        let crosstab = CrossTab {
+            row_question_id: 3,
+            row_question_text: "Country".to_string(),
+            column_question_id: 2,
+            column_question_text: "RemoteWork".to_string(),
+            row_options: vec!["India".to_string()],
+            column_options: vec!["Remote".to_string(), "Hybrid".to_string()],
+            counts: vec![vec![3.0, 1.0]],
+            total_responses: 4,
+            effective_sample_size: 4.0,
+            weighted: false,
+        };
+        let spec = crosstab.to_vega_lite();
+        assert_eq!(spec["data"]["values"].as_array().unwrap().len(), 2);
+        assert_eq!(spec["data"]["values"][1]["percentage"], 25.0);
+        assert_eq!(spec["encoding"]["color"]["sort"], json!(["Remote", "Hybrid"]));
+
+        let histogram = Histogram::from_values("YearsCode", &[(1.0, 1.0), (3.0, 1.0)], Some(2));
+        let spec = histogram.to_vega_lite();
+        assert_eq!(spec["data"]["values"][1], json!({"lower": 2.0, "upper": 3.0, "count": 1.0}));
+        assert_eq!(spec["encoding"]["x"]["bin"]["binned"], true);
+    }
+}
@@ -0,0 +1,111 @@
+//! JavaScript bindings for in-browser exploration, built with the `wasm` feature
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::analysis::Subset;
+use crate::matcher::SearchMode;
+use crate::SurveyAnalyzer;
+
+/// Convert a result to plain JS objects and arrays (maps become objects, not `Map`s)
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value.serialize(&serializer).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// Survey loaded from the bytes of an Excel file; questions are column names or IDs
+#[wasm_bindgen(js_name = SurveyAnalyzer)]
+pub struct WasmSurveyAnalyzer {
+    inner: SurveyAnalyzer,
+}
+
+#[wasm_bindgen(js_class = SurveyAnalyzer)]
+impl WasmSurveyAnalyzer {
+    /// Load a survey from file contents, e.g. `new Uint8Array(await file.arrayBuffer())`
+    #[wasm_bindgen(constructor)]
+    pub fn new(bytes: &[u8]) -> Result<WasmSurveyAnalyzer, JsError> {
+        Ok(Self { inner: SurveyAnalyzer::from_excel_bytes(bytes)? })
+    }
+
+    #[wasm_bindgen(getter, js_name = respondentCount)]
+    pub fn respondent_count(&self) -> usize {
+        self.inner.survey().respondent_count()
+    }
+
+    /// All questions
+    pub fn structure(&self) -> Result<JsValue, JsError> {
+        to_js(&self.inner.get_survey_structure())
+    }
+
+    /// Ranked question matches, or option matches with `options`; `mode` is substring, regex or fuzzy
+    pub fn search(&self, term: &str, mode: Option<String>, options: Option<bool>) -> Result<JsValue, JsError> {
+        let mode: SearchMode = match mode {
+            Some(mode) => mode.parse().map_err(|e: String| JsError::new(&e))?,
+            None => SearchMode::default(),
+        };
+        let matches = if options.unwrap_or(false) {
+            self.inner.search_options_with(term, mode)?
+        } else {
+            self.inner.search_questions_with(term, mode)?
+        };
+        to_js(&matches)
+    }
+
+    /// Answer distribution over all respondents
+    pub fn distribution(&self, question: &str) -> Result<JsValue, JsError> {
+        to_js(&self.inner.get_distribution(question)?)
+    }
+
+    /// Answer distribution among a subset's respondents. Takes the subset by reference (wasm-bindgen
+    /// has no `Option<&T>` arguments), so the JS handle stays usable afterwards.
+    #[wasm_bindgen(js_name = subsetDistribution)]
+    pub fn subset_distribution(&self, question: &str, subset: &WasmSubset) -> Result<JsValue, JsError> {
+        to_js(&self.inner.get_subset_distribution(question, &subset.inner)?)
+    }
+
+    /// Respondents who chose `option`
+    pub fn subset(&self, question: &str, option: &str) -> Result<WasmSubset, JsError> {
+        Ok(WasmSubset { inner: self.inner.create_subset(question, option)? })
+    }
+}
+
+/// Respondents selected by an answer
+#[wasm_bindgen(js_name = Subset)]
+pub struct WasmSubset {
+    inner: Subset,
+}
+
+#[wasm_bindgen(js_class = Subset)]
+impl WasmSubset {
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.inner.size()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn label(&self) -> String {
+        self.inner.option.clone()
+    }
+
+    /// Share of all respondents, in percent
+    #[wasm_bindgen(getter)]
+    pub fn percentage(&self) -> f64 {
+        self.inner.percentage()
+    }
+
+    pub fn union(&self, other: &WasmSubset) -> WasmSubset {
+        WasmSubset { inner: self.inner.union(&other.inner) }
+    }
+
+    pub fn intersection(&self, other: &WasmSubset) -> WasmSubset {
+        WasmSubset { inner: self.inner.intersection(&other.inner) }
+    }
+
+    pub fn difference(&self, other: &WasmSubset) -> WasmSubset {
+        WasmSubset { inner: self.inner.difference(&other.inner) }
+    }
+
+    #[wasm_bindgen(js_name = toJSON)]
+    pub fn to_json(&self) -> Result<JsValue, JsError> {
+        to_js(&self.inner)
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::error::{SurveyError, Result};
+use crate::survey::{QuestionRef, Survey};
+
+/// Maximum change in any adjustment factor at which raking is considered converged
+const RAKING_TOLERANCE: f64 = 1e-6;
+
+/// Per-respondent weights, indexed by respondent ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weights {
+    values: Vec<f64>,
+}
+
+/// Target marginal distribution of a question used for raking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RakeTarget {
+    #[serde(alias = "question_id")]
+    pub question: QuestionRef, // column position or name
+    pub marginals: HashMap<String, f64>, // option -> target share (normalized to sum to 1)
+}
+
+impl Weights {
+    /// Weight every respondent equally
+    pub fn uniform(respondent_count: usize) -> Self {
+        Self { values: vec![1.0; respondent_count] }
+    }
+
+    /// Create weights from explicit per-respondent values
+    pub fn from_values(values: Vec<f64>) -> Result<Self> {
+        if let Some(bad) = values.iter().find(|w| !w.is_finite() || **w < 0.0) {
+            return Err(SurveyError::DataParsingError(format!("Invalid weight: {}", bad)));
+        }
+        Ok(Self { values })
+    }
+
+    /// Read weights from a numeric column; respondents without a value get weight 0
+    pub fn from_column(survey: &Survey, question: impl Into<QuestionRef>) -> Result<Self> {
+        let question_id = survey.resolve_question(question)?;
+
+        let mut values = vec![0.0; survey.respondent_count()];
+        for answer in survey.answers().iter().filter(|a| a.question_id == question_id) {
+            let weight: f64 = answer.value.parse().map_err(|_| {
+                SurveyError::DataParsingError(format!(
+                    "Invalid weight '{}' for respondent {}",
+                    answer.value, answer.respondent_id
+                ))
+            })?;
+            if let Some(slot) = values.get_mut(answer.respondent_id) {
+                *slot = weight;
+            }
+        }
+
+        Self::from_values(values)
+    }
+
+    /// Post-stratify by iterative proportional fitting (raking) against target marginals.
+    /// Respondents without an answer listed in a target are left untouched by that target.
+    pub fn rake(survey: &Survey, targets: &[RakeTarget], max_iterations: usize) -> Result<Self> {
+        let mut categories = Vec::with_capacity(targets.len());
+        for target in targets {
+            let question_id = survey.resolve_question(&target.question)?;
+
+            let target_total: f64 = target.marginals.values().sum();
+            if target_total <= 0.0 || target.marginals.values().any(|share| *share < 0.0) {
+                return Err(SurveyError::DataParsingError(format!(
+                    "Invalid target marginals for question {}",
+                    target.question
+                )));
+            }
+
+            let mut by_respondent = vec![None; survey.respondent_count()];
+            for answer in survey.answers().iter().filter(|a| a.question_id == question_id) {
+                if let Some(share) = target.marginals.get(&answer.value) {
+                    // Zeroing these respondents would leave nothing to scale on the next pass
+                    if *share == 0.0 {
+                        return Err(SurveyError::InvalidParameter(format!(
+                            "Target share of '{}' for question {} is 0 but respondents chose it",
+                            answer.value, target.question
+                        )));
+                    }
+                    if let Some(slot) = by_respondent.get_mut(answer.respondent_id) {
+                        *slot = Some((answer.value.as_str(), share / target_total));
+                    }
+                }
+            }
+            categories.push(by_respondent);
+        }
+
+        let mut values = vec![1.0; survey.respondent_count()];
+        for _ in 0..max_iterations {
+            let mut max_change: f64 = 0.0;
+
+            for by_respondent in &categories {
+                let mut current: HashMap<&str, f64> = HashMap::new();
+                let mut classified_total = 0.0;
+                for (respondent_id, category) in by_respondent.iter().enumerate() {
+                    if let Some((option, _)) = category {
+                        *current.entry(option).or_insert(0.0) += values[respondent_id];
+                        classified_total += values[respondent_id];
+                    }
+                }
+
+                for (respondent_id, category) in by_respondent.iter().enumerate() {
+                    if let Some((option, share)) = category {
+                        let factor = share * classified_total / current[option];
+                        values[respondent_id] *= factor;
+                        max_change = max_change.max((factor - 1.0).abs());
+                    }
+                }
+            }
+
+            if max_change < RAKING_TOLERANCE {
+                return Ok(Self { values });
+            }
+        }
+
+        Err(SurveyError::RakingFailed(max_iterations))
+    }
+
+    /// Get the weight of a respondent (0 if unknown)
+    pub fn get(&self, respondent_id: usize) -> f64 {
+        self.values.get(respondent_id).copied().unwrap_or(0.0)
+    }
+
+    /// Get all weights, indexed by respondent ID
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Kish effective sample size (sum of weights squared over sum of squared weights)
+    pub fn effective_sample_size<I: IntoIterator<Item = usize>>(&self, respondent_ids: I) -> f64 {
+        let (sum, sum_squares) = respondent_ids
+            .into_iter()
+            .map(|id| self.get(id))
+            .fold((0.0, 0.0), |(sum, sum_squares), w| (sum + w, sum_squares + w * w));
+
+        if sum_squares > 0.0 {
+            sum * sum / sum_squares
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_sample_size() {
+        let uniform = Weights::uniform(4);
+        assert_eq!(uniform.effective_sample_size(0..4), 4.0);
+
+        let skewed = Weights::from_values(vec![1.0, 1.0, 2.0, 0.0]).unwrap();
+        assert!((skewed.effective_sample_size(0..4) - 16.0 / 6.0).abs() < 1e-12);
+        assert_eq!(skewed.get(10), 0.0);
+
+        assert!(Weights::from_values(vec![1.0, -1.0]).is_err());
+    }
+
+    #[test]
+    fn test_rake_rejects_zero_share_of_chosen_option() {
+        let survey = Survey::from_csv_reader("Country\nGermany\nGermany\nPoland\n".as_bytes()).unwrap();
+        let target = |poland: f64, france: f64| RakeTarget {
+            question: QuestionRef::from("Country"),
+            marginals: HashMap::from([
+                ("Germany".to_string(), 0.5),
+                ("Poland".to_string(), poland),
+                ("France".to_string(), france),
+            ]),
+        };
+
+        let error = Weights::rake(&survey, &[target(0.0, 0.5)], 50).unwrap_err();
+        assert!(matches!(error, SurveyError::InvalidParameter(_)), "{}", error);
+
+        // A zero share for an option nobody chose is harmless
+        let weights = Weights::rake(&survey, &[target(0.5, 0.0)], 50).unwrap();
+        assert!(weights.values().iter().all(|w| w.is_finite()));
+        assert!((weights.get(2) - 1.5).abs() < 1e-9);
+    }
+}
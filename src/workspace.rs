@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::error::{SurveyError, Result};
+use crate::survey::QuestionRef;
+
+/// Workspace file format version written by this crate
+pub const WORKSPACE_VERSION: u32 = 1;
+
+/// An input file pinned by its SHA-256 hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataFile {
+    pub path: PathBuf,
+    pub sha256: String, // lowercase hex
+}
+
+impl DataFile {
+    /// Hash a file, recording its absolute path
+    pub fn hash<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = std::fs::canonicalize(path)?;
+        let sha256 = sha256_file(&path)?;
+        Ok(Self { path, sha256 })
+    }
+
+    /// Fail with `DataFileChanged` unless the file still has the recorded hash
+    pub fn verify(&self) -> Result<()> {
+        let actual = sha256_file(&self.path)?;
+        if actual != self.sha256 {
+            return Err(SurveyError::DataFileChanged {
+                path: self.path.display().to_string(),
+                expected: self.sha256.clone(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Options the data was loaded with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight_column: Option<QuestionRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rake: Option<DataFile>, // raking targets, pinned like the data
+}
+
+/// A named subset and the expression that defined it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSubset {
+    pub name: String,
+    pub definition: String,
+}
+
+/// A saved analysis session: pinned inputs, load options, named state and the command log to replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub version: u32,
+    pub data: DataFile,
+    #[serde(default)]
+    pub options: LoadOptions,
+    #[serde(default)]
+    pub subsets: Vec<SavedSubset>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    #[serde(default)]
+    pub commands: Vec<String>, // in execution order, variables already expanded
+}
+
+impl Workspace {
+    /// Start an empty workspace for a data file
+    pub fn new(data: DataFile, options: LoadOptions) -> Self {
+        Self {
+            version: WORKSPACE_VERSION,
+            data,
+            options,
+            subsets: Vec::new(),
+            variables: BTreeMap::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    /// Read a workspace file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let workspace: Workspace = serde_json::from_reader(BufReader::new(File::open(path)?))
+            .map_err(|e| SurveyError::DataParsingError(format!("Invalid workspace file: {}", e)))?;
+        if workspace.version > WORKSPACE_VERSION {
+            return Err(SurveyError::DataParsingError(format!(
+                "Workspace version {} is newer than the supported version {}",
+                workspace.version, WORKSPACE_VERSION
+            )));
+        }
+        Ok(workspace)
+    }
+
+    /// Write the workspace as pretty-printed JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| SurveyError::DataParsingError(format!("Could not serialize workspace: {}", e)))?;
+        std::fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// Check that the data and raking targets are unchanged since the workspace was saved
+    pub fn verify(&self) -> Result<()> {
+        self.data.verify()?;
+        if let Some(rake) = &self.options.rake {
+            rake.verify()?;
+        }
+        Ok(())
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_round_trip_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("survey.csv");
+        std::fs::write(&data_path, "abc").unwrap();
+
+        let data = DataFile::hash(&data_path).unwrap();
+        assert_eq!(data.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+        let mut workspace = Workspace::new(
+            data,
+            LoadOptions { weight_column: Some(QuestionRef::Name("Weight".to_string())), rake: None },
+        );
+        workspace.subsets.push(SavedSubset { name: "remote".to_string(), definition: "subset RemoteWork Remote".to_string() });
+        workspace.commands.push("let remote = subset RemoteWork Remote".to_string());
+        let path = dir.path().join("session.json");
+        workspace.save(&path).unwrap();
+
+        let reopened = Workspace::open(&path).unwrap();
+        assert_eq!(reopened, workspace);
+        reopened.verify().unwrap();
+
+        std::fs::write(&data_path, "abd").unwrap();
+        assert!(matches!(reopened.verify(), Err(SurveyError::DataFileChanged { .. })));
+    }
+}
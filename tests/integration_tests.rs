@@ -60,6 +60,7 @@ fn test_distribution_analysis() {
         question_type: QuestionType::SingleChoice,
         distribution,
         total_responses: 200,
+        intervals: HashMap::new(),
     };
     
     let most_popular = answer_dist.most_popular().unwrap();
@@ -80,6 +81,7 @@ fn test_subset_operations() {
         option: "Rust".to_string(),
         respondent_ids: vec![1, 2, 3, 4, 5],
         total_respondents: 100,
+        group: None,
     };
     
     let subset2 = Subset {
@@ -87,6 +89,7 @@ fn test_subset_operations() {
         option: "Senior".to_string(),
         respondent_ids: vec![3, 4, 5, 6, 7],
         total_respondents: 100,
+        group: None,
     };
     
     // Test subset properties
@@ -106,6 +109,7 @@ fn test_cli_help_functionality() {
     // Test that the CLI binary exists and shows help
     use std::process::Command;
     
+    #[allow(clippy::needless_borrows_for_generic_args)]
     let output = Command::new("cargo")
         .args(&["run", "--bin", "so_survey_cli", "--", "--help"])
         .current_dir(env!("CARGO_MANIFEST_DIR"))
@@ -120,9 +124,191 @@ fn test_cli_help_functionality() {
             assert!(stdout.contains("distribution"));
             assert!(stdout.contains("subset"));
             assert!(stdout.contains("repl"));
+            assert!(stdout.contains("--format"));
         }
         Err(_) => {
             println!("Could not run CLI command (expected in some test environments)");
         }
     }
-}
\ No newline at end of file
+}
+
+/// Write a small survey workbook: respondent ID, remote work and primary language
+#[cfg(feature = "cli")]
+fn write_test_workbook(path: &std::path::Path) {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let sheet = workbook.add_worksheet();
+    for (col, header) in ["ResponseId", "RemoteWork", "Language"].iter().enumerate() {
+        sheet.write_string(0, col as u16, *header).unwrap();
+    }
+    for row in 1..=10u32 {
+        let remote = if row <= 6 { "Remote" } else { "In-person" };
+        let language = if row % 2 == 0 { "Rust" } else { "Go" };
+        sheet.write_number(row, 0, row as f64).unwrap();
+        sheet.write_string(row, 1, remote).unwrap();
+        sheet.write_string(row, 2, language).unwrap();
+    }
+    workbook.save(path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_batch_script() {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("survey.xlsx");
+    write_test_workbook(&data);
+    let script = dir.path().join("explore.survey");
+    std::fs::write(
+        &script,
+        "# Rust users who work remotely\n\
+         let remote = subset RemoteWork Remote\n\
+         let rust = subset Language $language\n\
+         let both = remote & rust\n\
+         dist RemoteWork in both > remote_rust.txt\n\
+         subsets\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+        .args(["--file", data.to_str().unwrap(), "run", script.to_str().unwrap(), "--var", "language=Rust"])
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("both = remote & rust: 3 respondents (30.0% of total)"));
+    let redirected = std::fs::read_to_string(dir.path().join("remote_rust.txt")).unwrap();
+    assert!(redirected.contains("Remote: 3 (100.0%)"));
+
+    // Without --var the script fails at the first use of $language and --fail-fast stops there
+    let output = Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+        .args(["--file", data.to_str().unwrap(), "run", "--fail-fast"])
+        .stdin(std::fs::File::open(&script).unwrap())
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("<stdin>:3: Invalid parameter: Unknown variable '$language'"));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("both ="));
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_weighted_distribution_rejects_intervals() {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("survey.xlsx");
+    write_test_workbook(&data);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+        .args(["--file", data.to_str().unwrap(), "--weight-column", "ResponseId"])
+        .args(["distribution", "RemoteWork", "--ci", "0.95"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Confidence intervals are not available for weighted data"), "{}", stderr);
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_unweighted_commands_reject_weights() {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("survey.xlsx");
+    write_test_workbook(&data);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+        .args(["--file", data.to_str().unwrap(), "--weight-column", "ResponseId"])
+        .args(["compare", "Language", "--left", "RemoteWork:Remote", "--right", "RemoteWork:In-person"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("'compare' does not support weighting"), "{}", stderr);
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_rejects_format_without_results() {
+    use std::process::Command;
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("survey.xlsx");
+    write_test_workbook(&data);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+        .args(["--file", data.to_str().unwrap(), "--format", "json", "run", "-"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--format json is not supported by 'run'"), "{}", stderr);
+}
+
+#[test]
+#[cfg(feature = "cli")]
+fn test_cli_serve_json_api() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::process::{Command, Stdio};
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("survey.xlsx");
+    write_test_workbook(&data);
+
+    /// Stops the server even when an assertion fails
+    struct Server(std::process::Child);
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    let mut server = Server(
+        Command::new(env!("CARGO_BIN_EXE_so_survey_cli"))
+            .args(["--file", data.to_str().unwrap(), "serve", "--port", "0"])
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    );
+    let mut stderr = BufReader::new(server.0.stderr.take().unwrap()).lines().map(Result::unwrap);
+    let address = stderr
+        .find_map(|line| line.strip_prefix("Listening on http://").map(str::to_string))
+        .expect("server prints its address");
+    // Keep draining the request log so the server never writes to a closed pipe
+    std::thread::spawn(move || stderr.for_each(|line| eprintln!("{}", line)));
+
+    let get = |target: &str| -> (String, serde_json::Value) {
+        let mut stream = std::net::TcpStream::connect(&address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", target).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.lines().next().unwrap().to_string(), serde_json::from_str(body).unwrap())
+    };
+
+    let (status, body) = get("/distribution?question=RemoteWork");
+    assert!(status.contains("200"), "{}", status);
+    assert_eq!(body["distribution"]["Remote"][0], 6);
+
+    let (status, body) = get("/subset?question=Language&option=Rust");
+    assert!(status.contains("200"));
+    assert_eq!(body["respondent_ids"].as_array().unwrap().len(), 5);
+
+    let (status, body) = get("/structure");
+    assert!(status.contains("200"));
+    assert_eq!(body.as_array().unwrap().len(), 3);
+
+    let (status, body) = get("/distribution?question=Remote%20Work");
+    assert!(status.contains("404"), "{}", status);
+    assert_eq!(body["status"], 404);
+    assert!(body["error"].as_str().unwrap().contains("Question not found"));
+
+    let (status, _) = get("/crosstab?row=RemoteWork");
+    assert!(status.contains("400"));
+}